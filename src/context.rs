@@ -1,7 +1,9 @@
 use crate::fn2::FN2;
 use crate::graphics::Graphics;
+use crate::Layers;
 use crate::Level;
 use crate::TextureType;
+use crate::Tile;
 use crate::Trigonometry;
 use sdl2::render::Canvas;
 use sdl2::render::Texture;
@@ -9,6 +11,11 @@ use sdl2::render::TextureCreator;
 use sdl2::video::Window;
 use sdl2::video::WindowContext;
 use sdl2::Sdl;
+use utk_level::area::Clipboard;
+use utk_level::autotile::AutoTileRules;
+use utk_level::brush::Brush;
+use utk_level::history::History;
+use utk_level::variation::VariationBrush;
 
 pub struct Textures<'a> {
     pub floor: Texture<'a>,
@@ -34,4 +41,20 @@ pub struct Context<'a> {
     pub level_save_name: String,
     pub trigonometry: Trigonometry,
     pub automatic_shadows: bool,
+    pub automatic_walls: bool,
+    pub auto_tile_rules: AutoTileRules,
+    pub history: History,
+    pub clipboard: Option<Clipboard>,
+    // Painted instead of the selected tile when set
+    pub variation_brush: Option<VariationBrush>,
+    pub brush: Brush,
+    // Left behind by the eraser
+    pub eraser_tile: Tile,
+    pub eraser_removes_objects: bool,
+    pub show_minimap: bool,
+    // Scroll when a drag reaches the edge of the window
+    pub edge_scrolling: bool,
+    pub layers: Layers,
+    pub show_grid: bool,
+    pub show_lighting: bool,
 }
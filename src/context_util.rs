@@ -1,4 +1,3 @@
-use crate::crates;
 use crate::create_text_texture;
 use crate::fn2::FN2;
 use crate::sdl2::image::LoadTexture;
@@ -10,6 +9,7 @@ use sdl2::render::Canvas;
 use sdl2::render::TextureCreator;
 use sdl2::video::Window;
 use sdl2::video::WindowContext;
+use utk_level::crates;
 
 fn refresh(context: &mut Context, window_size: (u32, u32)) {
     context.graphics.resolution_x = window_size.0;
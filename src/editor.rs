@@ -1,14 +1,10 @@
 extern crate sdl2;
 
 use crate::context_util::resize;
-use crate::crates::{get_crates, CrateClass};
 use crate::create_text_texture;
 use crate::editor_textures::EditorTextures;
-use crate::level::StaticCrate;
-use crate::level::StaticCrateType;
-use crate::level::Steam;
 use crate::render;
-use crate::types::GameType;
+use crate::types::{GameType, Layers, Tile, Tiles};
 use crate::util::*;
 use crate::Context;
 use crate::Graphics;
@@ -18,9 +14,40 @@ use crate::NextMode::*;
 use crate::TextureType;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::keyboard::Mod;
+use sdl2::keyboard::Scancode;
 use sdl2::mouse::MouseButton;
 use sdl2::render::Texture;
 use sdl2::render::TextureQuery;
+use std::collections::{BTreeMap, BTreeSet};
+use utk_level::area::Area;
+use utk_level::brush::{BrushShape, MAX_BRUSH_SIZE};
+use utk_level::crates::{get_crates, CrateClass};
+use utk_level::fill::{Connectivity, FillOptions};
+use utk_level::generate::{generate_level, GeneratorOptions};
+use utk_level::level::StaticCrate;
+use utk_level::level::StaticCrateType;
+use utk_level::level::Steam;
+use utk_level::level::{OLDEST_VERSION, VERSION};
+use utk_level::objects::{ObjectChange, ObjectKind, ObjectRef};
+use utk_level::render::{render_level, Assets, RenderOptions};
+use utk_level::resize::Resize;
+use utk_level::shape::Shape;
+use utk_level::transform::Transform;
+
+// Left behind where tiles are cut out, also pads resized levels when no
+// floor or wall tile is selected
+const DEFAULT_FILL_TILE: Tile = Tile {
+    texture_type: TextureType::FLOOR,
+    id: 0,
+    shadow: 0,
+};
+// Screen pixels per frame
+const KEY_SCROLL_SPEED: i32 = 12;
+const EDGE_SCROLL_SPEED: i32 = 8;
+
+// Spotlights and level size a light texture was made for
+type LightKey = (BTreeMap<(u32, u32), u8>, (u32, u32));
 
 #[derive(PartialEq)]
 enum NewLevelState {
@@ -47,9 +74,92 @@ enum PromptType {
     NewLevel(NewLevelState),
     Save(SaveLevelType),
     CreateShadows(ShadowPromptType),
+    Resize,
+    Generate,
     Quit,
 }
 
+// Signed tile counts typed into the resize prompt
+#[derive(Default)]
+struct ResizeFields {
+    // left, right, top, bottom
+    edges: [String; 4],
+    selected: usize,
+}
+
+impl ResizeFields {
+    fn get_resize(&self) -> Resize {
+        let [left, right, top, bottom] = self.edges.clone().map(|edge| edge.parse().unwrap_or(0));
+        Resize {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+}
+
+// Generator options typed into the new level prompt
+struct GenerateFields {
+    // seed, room count, corridor width, wall thickness
+    numbers: [String; 4],
+    spotlights: bool,
+    crates: bool,
+    // numbers first, then spotlights and crates
+    selected: usize,
+    error: Option<String>,
+}
+
+const GENERATE_FIELD_COUNT: usize = 6;
+
+impl GenerateFields {
+    fn new() -> GenerateFields {
+        let options = GeneratorOptions::default();
+        // Any seed will do, it is shown so that a good level can be made again
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.subsec_nanos() % 1_000_000);
+        GenerateFields {
+            numbers: [
+                seed.to_string(),
+                options.rooms.to_string(),
+                options.corridor_width.to_string(),
+                options.wall_thickness.to_string(),
+            ],
+            spotlights: options.spotlights,
+            crates: options.crates,
+            selected: 0,
+            error: None,
+        }
+    }
+
+    fn get_options(&self, size: (u8, u8)) -> GeneratorOptions {
+        let [_, rooms, corridor_width, wall_thickness] = self
+            .numbers
+            .clone()
+            .map(|number| number.parse().unwrap_or(0));
+        GeneratorOptions {
+            size,
+            rooms,
+            corridor_width,
+            wall_thickness,
+            spotlights: self.spotlights,
+            crates: self.crates,
+        }
+    }
+}
+
+// Used on the level instead of dragging filled rectangles
+#[derive(Clone, Copy, PartialEq)]
+enum Tool {
+    None,
+    Fill,
+    Shape(Shape),
+    Brush,
+    Eraser,
+    Move,
+}
+
 #[derive(PartialEq)]
 enum InsertState {
     Instructions((u32, u32)), // level coordinates of currently manipulated item
@@ -69,6 +179,7 @@ enum InsertType {
 pub fn exec(context: &mut Context) -> NextMode {
     let mut textures = EditorTextures::new(context);
     let mut set_position: u8 = 0;
+    // Level tile where the left button went down
     let mut mouse_left_click: Option<(u32, u32)> = None;
     let mut mouse_right_click = false;
     let mut prompt = PromptType::None;
@@ -77,11 +188,45 @@ pub fn exec(context: &mut Context) -> NextMode {
     let mut new_level_size_x: String = DEFAULT_LEVEL_SIZE.0.to_string();
     let mut new_level_size_y: String = DEFAULT_LEVEL_SIZE.1.to_string();
     let mut drag_tiles = false;
+    let mut save_error_texture: Option<Texture> = None;
+    let mut save_version = VERSION;
+    // One line per loss of saving as the version
+    let mut loss_textures: Option<(u32, Vec<Texture>)> = None;
+    let mut status_text_texture: Option<Texture> = None;
+    let mut resize_fields = ResizeFields::default();
+    let mut generate_new_level = false;
+    let mut generate_fields = GenerateFields::new();
+    let mut selection: Option<Area> = None;
+    let mut selection_anchor: Option<(u32, u32)> = None;
+    let mut pasting = false;
+    let mut tool = Tool::None;
+    let mut fill_options = FillOptions::default();
+    // Tile where the shape being drawn was started
+    let mut shape_start: Option<(u32, u32)> = None;
+    let mut dragging_minimap = false;
+    let mut light_texture: Option<(LightKey, Texture)> = None;
+    let mut minimap_texture: Option<(Tiles, Texture)> = None;
+    let mut hover_text: Option<(String, Texture)> = None;
+    // Mouse and scroll when the middle button went down
+    let mut panning: Option<((u32, u32), (u32, u32))> = None;
+    let mut selected_objects: BTreeSet<ObjectRef> = BTreeSet::new();
+    // Level pixel where the selected objects are held while dragging them
+    let mut drag_point: Option<(i64, i64)> = None;
+    // Level pixel where the box around objects to select was started
+    let mut box_anchor: Option<(u32, u32)> = None;
     let crates = get_crates();
 
     let mut event_pump = context.sdl.event_pump().unwrap();
     loop {
         for event in event_pump.poll_iter() {
+            // Arrows scroll unless they adjust an item or move objects
+            let arrows_edit = is_editing_item(&insert_item)
+                || (tool == Tool::Move && !selected_objects.is_empty());
+            if matches!(event, Event::KeyDown { .. } | Event::MouseButtonDown { .. })
+                && !is_view_input(&event, context, arrows_edit)
+            {
+                context.history.begin(&context.level);
+            }
             match event {
                 Event::Quit { .. }
                 | Event::KeyDown {
@@ -91,10 +236,18 @@ pub fn exec(context: &mut Context) -> NextMode {
                     prompt = if prompt != PromptType::None
                         || insert_item != InsertType::None
                         || set_position > 0
+                        || selection.is_some()
+                        || pasting
+                        || tool != Tool::None
                     {
                         insert_item = InsertType::None;
                         context.sdl.video().unwrap().text_input().stop();
                         set_position = 0;
+                        selection = None;
+                        pasting = false;
+                        tool = Tool::None;
+                        shape_start = None;
+                        selected_objects.clear();
                         PromptType::None
                     } else {
                         PromptType::Quit
@@ -103,10 +256,10 @@ pub fn exec(context: &mut Context) -> NextMode {
                 Event::TextInput { text, .. } => match &prompt {
                     PromptType::NewLevel(new_level_state) => match new_level_state {
                         NewLevelState::XSize => {
-                            sanitize_numeric_input(&text, &mut new_level_size_x)
+                            sanitize_numeric_input(&text, &mut new_level_size_x, 3)
                         }
                         NewLevelState::YSize => {
-                            sanitize_numeric_input(&text, &mut new_level_size_y)
+                            sanitize_numeric_input(&text, &mut new_level_size_y, 3)
                         }
                         _ => {}
                     },
@@ -116,6 +269,20 @@ pub fn exec(context: &mut Context) -> NextMode {
                         }
                         _ => {}
                     },
+                    PromptType::Resize => sanitize_signed_input(
+                        &text,
+                        &mut resize_fields.edges[resize_fields.selected],
+                    ),
+                    PromptType::Generate => {
+                        let selected = generate_fields.selected;
+                        if let Some(number) = generate_fields.numbers.get_mut(selected) {
+                            sanitize_numeric_input(
+                                &text,
+                                number,
+                                if selected == 0 { 9 } else { 2 },
+                            );
+                        }
+                    }
                     _ => (),
                 },
                 Event::Window { win_event, .. } => {
@@ -123,7 +290,308 @@ pub fn exec(context: &mut Context) -> NextMode {
                         textures = EditorTextures::new(context);
                     }
                 }
+                Event::KeyDown {
+                    keycode:
+                        Some(
+                            key @ (Keycode::Z
+                            | Keycode::Y
+                            | Keycode::C
+                            | Keycode::X
+                            | Keycode::V
+                            | Keycode::R),
+                        ),
+                    keymod,
+                    ..
+                } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+                    && prompt == PromptType::None
+                    && mouse_left_click.is_none() =>
+                {
+                    match key {
+                        Keycode::Z | Keycode::Y => {
+                            if key == Keycode::Z {
+                                context.history.undo(&mut context.level);
+                            } else {
+                                context.history.redo(&mut context.level);
+                            }
+                            // Item being edited may be gone after undo
+                            insert_item = InsertType::None;
+                            set_position = 0;
+                            limit_scroll(context);
+                        }
+                        Keycode::C => {
+                            if let Some(area) = &selection {
+                                context.clipboard = Some(context.level.copy_area(area));
+                            }
+                        }
+                        Keycode::X => {
+                            if let Some(area) = &selection {
+                                context.clipboard =
+                                    Some(context.level.cut_area(area, &DEFAULT_FILL_TILE));
+                                update_walls_and_shadows(context, area);
+                            }
+                        }
+                        Keycode::R => {
+                            let message = match (&selection, &mut context.variation_brush) {
+                                (Some(area), Some(brush)) => {
+                                    brush.seed = brush.seed.wrapping_add(1);
+                                    let seed = brush.seed;
+                                    if let Some(changed) = context.level.randomize_area(area, brush)
+                                    {
+                                        finish_tile_changes(context, &changed);
+                                    }
+                                    format!("random brush seed {}", seed)
+                                }
+                                (None, _) => "select an area to re-randomize".to_string(),
+                                (_, None) => "no random brush, SHIFT+CLICK tiles in tile selection"
+                                    .to_string(),
+                            };
+                            status_text_texture = Some(create_text_texture(
+                                &mut context.canvas,
+                                context.texture_creator,
+                                &context.font,
+                                &message,
+                            ));
+                        }
+                        _ => {
+                            if context.clipboard.is_some() {
+                                insert_item = InsertType::None;
+                                set_position = 0;
+                                pasting = true;
+                            }
+                        }
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(key),
+                    keymod,
+                    ..
+                } if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+                    && prompt == PromptType::None
+                    && (matches!(key, Keycode::G | Keycode::L)
+                        || get_layer_index(key).is_some()) =>
+                {
+                    let (name, visible) = match get_layer_index(key) {
+                        Some(index) => (Layers::NAMES[index], context.layers.toggle(index)),
+                        None if key == Keycode::L => {
+                            context.show_lighting = !context.show_lighting;
+                            ("lighting", context.show_lighting)
+                        }
+                        None => {
+                            context.show_grid = !context.show_grid;
+                            ("grid", context.show_grid)
+                        }
+                    };
+                    status_text_texture = Some(create_text_texture(
+                        &mut context.canvas,
+                        context.texture_creator,
+                        &context.font,
+                        &format!("{} {}", name, if visible { "shown" } else { "hidden" }),
+                    ));
+                }
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } if prompt == PromptType::Resize => match key {
+                    Keycode::Up => {
+                        resize_fields.selected = (resize_fields.selected + 3) % 4;
+                    }
+                    Keycode::Down | Keycode::Tab => {
+                        resize_fields.selected = (resize_fields.selected + 1) % 4;
+                    }
+                    Keycode::Backspace => {
+                        resize_fields.edges[resize_fields.selected].pop();
+                    }
+                    Keycode::Return | Keycode::KpEnter => {
+                        let fill = get_resize_fill_tile(context);
+                        let resize = resize_fields.get_resize();
+                        if let Ok(report) = context.level.resize(&resize, &fill) {
+                            let edges = get_resized_edges(&resize, get_level_size(&context.level));
+                            if let Some(area) = edges {
+                                update_walls_and_shadows(context, &area);
+                            }
+                            limit_scroll(context);
+                            selection = None;
+                            context.sdl.video().unwrap().text_input().stop();
+                            status_text_texture = Some(create_text_texture(
+                                &mut context.canvas,
+                                context.texture_creator,
+                                &context.font,
+                                &format!(
+                                    "resized to {}x{}, {}",
+                                    context.level.tiles[0].len(),
+                                    context.level.tiles.len(),
+                                    report
+                                ),
+                            ));
+                            prompt = PromptType::None;
+                        }
+                    }
+                    _ => (),
+                },
+                Event::KeyDown {
+                    keycode: Some(key @ (Keycode::H | Keycode::V | Keycode::R)),
+                    keymod,
+                    ..
+                } if prompt == PromptType::None
+                    && insert_item == InsertType::None
+                    && set_position == 0
+                    && mouse_left_click.is_none() =>
+                {
+                    status_text_texture = None;
+                    let transform = match key {
+                        Keycode::H => Transform::FlipHorizontal,
+                        Keycode::V => Transform::FlipVertical,
+                        _ if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                            Transform::RotateCounterClockwise
+                        }
+                        _ => Transform::RotateClockwise,
+                    };
+                    if pasting {
+                        context.clipboard = context
+                            .clipboard
+                            .as_ref()
+                            .map(|clipboard| clipboard.transform(transform));
+                    } else if let Some(area) = selection {
+                        let transformed =
+                            context
+                                .level
+                                .transform_area(&area, transform, &DEFAULT_FILL_TILE);
+                        selection = Some(transformed);
+                        // Rotating a selection that is not square also
+                        // changes tiles outside of it
+                        let changed = Area::from_tiles([
+                            (area.x, area.y),
+                            (
+                                (area.x + area.width).max(transformed.x + transformed.width) - 1,
+                                (area.y + area.height).max(transformed.y + transformed.height) - 1,
+                            ),
+                        ]);
+                        if let Some(changed) = changed {
+                            update_walls_and_shadows(context, &changed);
+                        }
+                    } else {
+                        match context.level.transform(transform) {
+                            Ok(()) => {
+                                let (width, height) = get_level_size(&context.level);
+                                let level_area = Area {
+                                    x: 0,
+                                    y: 0,
+                                    width,
+                                    height,
+                                };
+                                update_walls_and_shadows(context, &level_area);
+                            }
+                            Err(error) => {
+                                status_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &format!("rotate failed: {}", error),
+                                ));
+                            }
+                        }
+                    }
+                    limit_scroll(context);
+                }
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } if prompt == PromptType::Generate => match key {
+                    Keycode::Up => {
+                        generate_fields.selected =
+                            (generate_fields.selected + GENERATE_FIELD_COUNT - 1)
+                                % GENERATE_FIELD_COUNT;
+                    }
+                    Keycode::Down | Keycode::Tab => {
+                        generate_fields.selected =
+                            (generate_fields.selected + 1) % GENERATE_FIELD_COUNT;
+                    }
+                    Keycode::Left | Keycode::Right | Keycode::Space => {
+                        match generate_fields.selected {
+                            4 => generate_fields.spotlights = !generate_fields.spotlights,
+                            5 => generate_fields.crates = !generate_fields.crates,
+                            _ => (),
+                        }
+                    }
+                    Keycode::Backspace => {
+                        if let Some(number) =
+                            generate_fields.numbers.get_mut(generate_fields.selected)
+                        {
+                            number.pop();
+                        }
+                    }
+                    Keycode::Return | Keycode::KpEnter => {
+                        let size = (
+                            new_level_size_x.parse().unwrap(),
+                            new_level_size_y.parse().unwrap(),
+                        );
+                        match generate_level(
+                            generate_fields.numbers[0].parse().unwrap_or(0),
+                            &generate_fields.get_options(size),
+                            &context.auto_tile_rules,
+                        ) {
+                            Ok(level) => {
+                                context.level = level;
+                                context.sdl.video().unwrap().text_input().stop();
+                                context.textures.saved_level_name = None;
+                                context.level_save_name.clear();
+                                context.history.clear();
+                                selection = None;
+                                prompt = PromptType::None;
+                            }
+                            Err(error) => generate_fields.error = Some(error.to_string()),
+                        }
+                    }
+                    _ => (),
+                },
+                Event::KeyDown {
+                    keycode:
+                        Some(key @ (Keycode::Up | Keycode::Down | Keycode::Left | Keycode::Right)),
+                    keymod,
+                    ..
+                } if tool == Tool::Move
+                    && !selected_objects.is_empty()
+                    && prompt == PromptType::None
+                    && mouse_left_click.is_none() =>
+                {
+                    if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                        let changes = match key {
+                            Keycode::Up => vec![
+                                ObjectChange::Intensity(1),
+                                ObjectChange::SteamRange(1),
+                                ObjectChange::CrateClass(1),
+                            ],
+                            Keycode::Down => vec![
+                                ObjectChange::Intensity(-1),
+                                ObjectChange::SteamRange(-1),
+                                ObjectChange::CrateClass(-1),
+                            ],
+                            Keycode::Left => {
+                                vec![ObjectChange::SteamAngle(-5), ObjectChange::CrateType(-1)]
+                            }
+                            _ => vec![ObjectChange::SteamAngle(5), ObjectChange::CrateType(1)],
+                        };
+                        let changed: usize = changes
+                            .into_iter()
+                            .map(|change| context.level.change_objects(&selected_objects, change))
+                            .sum();
+                        status_text_texture =
+                            Some(create_objects_text_texture(context, changed, "changed"));
+                    } else {
+                        let step = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                            context.graphics.tile_size as i64
+                        } else {
+                            1
+                        };
+                        let delta = match key {
+                            Keycode::Up => (0, -step),
+                            Keycode::Down => (0, step),
+                            Keycode::Left => (-step, 0),
+                            _ => (step, 0),
+                        };
+                        move_objects_by(context, &mut selected_objects, delta);
+                    }
+                }
                 Event::KeyDown { keycode, .. } => {
+                    status_text_texture = None;
                     if let Some(key) = keycode {
                         match key {
                             Keycode::Space => {
@@ -134,6 +602,8 @@ pub fn exec(context: &mut Context) -> NextMode {
                             }
                             Keycode::F2 => {
                                 context.sdl.video().unwrap().text_input().stop();
+                                save_error_texture = None;
+                                save_version = VERSION;
                                 prompt = PromptType::Save(SaveLevelType::Prompt);
                             }
                             Keycode::F3 => {
@@ -145,6 +615,155 @@ pub fn exec(context: &mut Context) -> NextMode {
                                 new_level_size_x = DEFAULT_LEVEL_SIZE.0.to_string();
                                 new_level_size_y = DEFAULT_LEVEL_SIZE.1.to_string();
                             }
+                            Keycode::G => {
+                                if prompt == PromptType::NewLevel(NewLevelState::Prompt) {
+                                    generate_new_level = true;
+                                    prompt = PromptType::NewLevel(NewLevelState::XSize);
+                                    context.sdl.video().unwrap().text_input().start();
+                                }
+                            }
+                            Keycode::F5 => {
+                                insert_item = InsertType::None;
+                                set_position = 0;
+                                pasting = false;
+                                resize_fields = ResizeFields::default();
+                                prompt = PromptType::Resize;
+                                context.sdl.video().unwrap().text_input().start();
+                            }
+                            Keycode::B | Keycode::L | Keycode::P | Keycode::E | Keycode::I => {
+                                if prompt == PromptType::None && mouse_left_click.is_none() {
+                                    insert_item = InsertType::None;
+                                    set_position = 0;
+                                    pasting = false;
+                                    selected_objects.clear();
+                                    tool = match key {
+                                        Keycode::B => Tool::Fill,
+                                        Keycode::L => Tool::Shape(match tool {
+                                            Tool::Shape(Shape::Line) => Shape::Rectangle,
+                                            Tool::Shape(Shape::Rectangle) => Shape::Ellipse,
+                                            _ => Shape::Line,
+                                        }),
+                                        Keycode::P => Tool::Brush,
+                                        Keycode::I => Tool::Move,
+                                        _ => Tool::Eraser,
+                                    };
+                                }
+                            }
+                            Keycode::LeftBracket | Keycode::RightBracket | Keycode::O
+                                if matches!(tool, Tool::Brush | Tool::Eraser) =>
+                            {
+                                let brush = &mut context.brush;
+                                match key {
+                                    Keycode::LeftBracket => brush.size = (brush.size - 1).max(1),
+                                    Keycode::RightBracket => {
+                                        brush.size = (brush.size + 1).min(MAX_BRUSH_SIZE)
+                                    }
+                                    _ => {
+                                        brush.shape = match brush.shape {
+                                            BrushShape::Square => BrushShape::Round,
+                                            BrushShape::Round => BrushShape::Square,
+                                        }
+                                    }
+                                }
+                                status_text_texture = Some(create_brush_text_texture(context));
+                            }
+                            Keycode::D | Keycode::F if tool == Tool::Eraser => {
+                                let message = if key == Keycode::D {
+                                    context.eraser_removes_objects =
+                                        !context.eraser_removes_objects;
+                                    if context.eraser_removes_objects {
+                                        "eraser removes objects".to_string()
+                                    } else {
+                                        "eraser keeps objects".to_string()
+                                    }
+                                } else if context.texture_type_selected == TextureType::FLOOR {
+                                    context.eraser_tile = Tile {
+                                        texture_type: TextureType::FLOOR,
+                                        id: context.selected_tile_id,
+                                        shadow: 0,
+                                    };
+                                    format!("eraser leaves floor tile {}", context.selected_tile_id)
+                                } else {
+                                    "select a floor tile for the eraser first".to_string()
+                                };
+                                status_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &message,
+                                ));
+                            }
+                            Keycode::D | Keycode::F if tool == Tool::Fill => {
+                                if key == Keycode::D {
+                                    fill_options.connectivity = match fill_options.connectivity {
+                                        Connectivity::Four => Connectivity::Eight,
+                                        Connectivity::Eight => Connectivity::Four,
+                                    };
+                                } else {
+                                    fill_options.floors_only = !fill_options.floors_only;
+                                }
+                                status_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &format!(
+                                        "fill {} neighbours{}",
+                                        match fill_options.connectivity {
+                                            Connectivity::Four => 4,
+                                            Connectivity::Eight => 8,
+                                        },
+                                        if fill_options.floors_only {
+                                            ", floors only"
+                                        } else {
+                                            ""
+                                        }
+                                    ),
+                                ));
+                            }
+                            Keycode::Delete | Keycode::N | Keycode::D
+                                if tool == Tool::Move
+                                    && !selected_objects.is_empty()
+                                    && prompt == PromptType::None =>
+                            {
+                                let (count, done) = match key {
+                                    Keycode::Delete => {
+                                        (context.level.delete_objects(&selected_objects), "deleted")
+                                    }
+                                    _ => (
+                                        context.level.change_objects(
+                                            &selected_objects,
+                                            ObjectChange::CrateVariant(if key == Keycode::N {
+                                                StaticCrate::Normal
+                                            } else {
+                                                StaticCrate::Deathmatch
+                                            }),
+                                        ),
+                                        "changed",
+                                    ),
+                                };
+                                status_text_texture =
+                                    Some(create_objects_text_texture(context, count, done));
+                            }
+                            Keycode::M => {
+                                if prompt == PromptType::None {
+                                    context.show_minimap = !context.show_minimap;
+                                }
+                            }
+                            Keycode::T => {
+                                if prompt == PromptType::None {
+                                    context.automatic_walls = !context.automatic_walls;
+                                    status_text_texture = Some(create_text_texture(
+                                        &mut context.canvas,
+                                        context.texture_creator,
+                                        &context.font,
+                                        if context.automatic_walls {
+                                            "automatic wall pieces enabled"
+                                        } else {
+                                            "automatic wall pieces disabled"
+                                        },
+                                    ));
+                                }
+                            }
                             Keycode::F6 => {
                                 context.sdl.video().unwrap().text_input().stop();
                                 prompt = PromptType::CreateShadows(if context.automatic_shadows {
@@ -162,6 +781,28 @@ pub fn exec(context: &mut Context) -> NextMode {
                             Keycode::F9 => {
                                 return RandomItemEditor(GameType::Deathmatch);
                             }
+                            Keycode::F10 => {
+                                let message = save_screenshot(context);
+                                status_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &message,
+                                ));
+                            }
+                            Keycode::F11 => {
+                                context.edge_scrolling = !context.edge_scrolling;
+                                status_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    if context.edge_scrolling {
+                                        "edge scrolling enabled"
+                                    } else {
+                                        "edge scrolling disabled"
+                                    },
+                                ));
+                            }
                             Keycode::Num1 | Keycode::Num2 => {
                                 if !matches!(prompt, PromptType::NewLevel(_))
                                     && !matches!(prompt, PromptType::Save(_))
@@ -208,6 +849,7 @@ pub fn exec(context: &mut Context) -> NextMode {
                             Keycode::Y => match &prompt {
                                 PromptType::NewLevel(new_level_state) => match new_level_state {
                                     NewLevelState::Prompt => {
+                                        generate_new_level = false;
                                         prompt = PromptType::NewLevel(NewLevelState::XSize);
                                         context.sdl.video().unwrap().text_input().start();
                                     }
@@ -231,207 +873,128 @@ pub fn exec(context: &mut Context) -> NextMode {
                                     prompt = PromptType::None;
                                 }
                                 PromptType::Quit => return Quit,
-                                PromptType::None => {
+                                PromptType::None | PromptType::Resize | PromptType::Generate => {
                                     prompt = PromptType::None;
                                 }
                             },
                             Keycode::Up => match &insert_item {
-                                InsertType::Spotlight(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let spotlight_intensity =
-                                            context.level.get_spotlight_from_level(&coordinates);
-                                        context.level.put_spotlight_to_level(
+                                InsertType::Spotlight(InsertState::Instructions(coordinates)) => {
+                                    let spotlight_intensity =
+                                        context.level.get_spotlight_from_level(&coordinates);
+                                    context.level.put_spotlight_to_level(
+                                        &coordinates,
+                                        spotlight_intensity + 1,
+                                    )
+                                }
+                                InsertType::Steam(InsertState::Instructions(coordinates)) => {
+                                    let steam = context.level.get_steam_from_level(&coordinates);
+                                    if steam.range < 6 {
+                                        context.level.put_steam_to_level(
                                             &coordinates,
-                                            spotlight_intensity + 1,
+                                            &Steam {
+                                                angle: steam.angle,
+                                                range: steam.range + 1,
+                                            },
                                         )
                                     }
-                                    _ => (),
-                                },
-                                InsertType::Steam(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let steam =
-                                            context.level.get_steam_from_level(&coordinates);
-                                        if steam.range < 6 {
-                                            context.level.put_steam_to_level(
-                                                &coordinates,
-                                                &Steam {
-                                                    angle: steam.angle,
-                                                    range: steam.range + 1,
-                                                },
-                                            )
-                                        }
-                                    }
-                                    _ => (),
-                                },
-                                InsertType::NormalCrate(state) | InsertType::DMCrate(state) => {
-                                    match state {
-                                        InsertState::Instructions(coordinates) => {
-                                            let mut crate_item = context
-                                                .level
-                                                .get_crate_from_level(&coordinates)
-                                                .clone();
-                                            if (crate_item.crate_class as u32)
-                                                < CrateClass::Energy as u32
-                                            {
-                                                crate_item.crate_type = 0;
-                                                crate_item.crate_class = CrateClass::from_u32(
-                                                    crate_item.crate_class as u32 + 1,
-                                                );
-                                                context
-                                                    .level
-                                                    .put_crate_to_level(&coordinates, &crate_item)
-                                            }
-                                        }
-                                        _ => (),
-                                    }
                                 }
-                                _ => {
-                                    if context.level.scroll.1 > 0 {
-                                        context.level.scroll.1 = context.level.scroll.1 - 1
+                                InsertType::NormalCrate(InsertState::Instructions(coordinates))
+                                | InsertType::DMCrate(InsertState::Instructions(coordinates)) => {
+                                    let mut crate_item =
+                                        context.level.get_crate_from_level(&coordinates).clone();
+                                    if let Some(crate_class) =
+                                        CrateClass::from_u32(crate_item.crate_class as u32 + 1)
+                                    {
+                                        crate_item.crate_type = 0;
+                                        crate_item.crate_class = crate_class;
+                                        context.level.put_crate_to_level(&coordinates, &crate_item)
                                     }
                                 }
+                                _ => (),
                             },
                             Keycode::Down => match &insert_item {
-                                InsertType::Spotlight(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let spotlight_intensity =
-                                            context.level.get_spotlight_from_level(&coordinates);
-                                        if spotlight_intensity > 0 {
-                                            context.level.put_spotlight_to_level(
-                                                &coordinates,
-                                                spotlight_intensity - 1,
-                                            )
-                                        }
-                                    }
-                                    _ => (),
-                                },
-                                InsertType::Steam(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let steam =
-                                            context.level.get_steam_from_level(&coordinates);
-                                        if steam.range > 0 {
-                                            context.level.put_steam_to_level(
-                                                &coordinates,
-                                                &Steam {
-                                                    angle: steam.angle,
-                                                    range: steam.range - 1,
-                                                },
-                                            )
-                                        }
-                                    }
-                                    _ => (),
-                                },
-                                InsertType::NormalCrate(state) | InsertType::DMCrate(state) => {
-                                    match state {
-                                        InsertState::Instructions(coordinates) => {
-                                            let mut crate_item = context
-                                                .level
-                                                .get_crate_from_level(&coordinates)
-                                                .clone();
-                                            if crate_item.crate_class as u32 > 0 {
-                                                crate_item.crate_type = 0;
-                                                crate_item.crate_class = CrateClass::from_u32(
-                                                    crate_item.crate_class as u32 - 1,
-                                                );
-                                                context
-                                                    .level
-                                                    .put_crate_to_level(&coordinates, &crate_item)
-                                            }
-                                        }
-                                        _ => (),
-                                    }
-                                }
-                                _ => {
-                                    if context.level.scroll.1
-                                        + context.graphics.get_full_y_tiles_per_screen()
-                                        < (context.level.tiles.len()) as u32
-                                    {
-                                        context.level.scroll.1 = context.level.scroll.1 + 1;
+                                InsertType::Spotlight(InsertState::Instructions(coordinates)) => {
+                                    let spotlight_intensity =
+                                        context.level.get_spotlight_from_level(&coordinates);
+                                    if spotlight_intensity > 0 {
+                                        context.level.put_spotlight_to_level(
+                                            &coordinates,
+                                            spotlight_intensity - 1,
+                                        )
                                     }
                                 }
-                            },
-                            Keycode::Left => match &insert_item {
-                                InsertType::Steam(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let steam =
-                                            context.level.get_steam_from_level(&coordinates);
+                                InsertType::Steam(InsertState::Instructions(coordinates)) => {
+                                    let steam = context.level.get_steam_from_level(&coordinates);
+                                    if steam.range > 0 {
                                         context.level.put_steam_to_level(
                                             &coordinates,
                                             &Steam {
-                                                angle: (steam.angle + 360 - 5) % 360,
-                                                range: steam.range,
+                                                angle: steam.angle,
+                                                range: steam.range - 1,
                                             },
                                         )
                                     }
-                                    _ => (),
-                                },
-                                InsertType::NormalCrate(state) | InsertType::DMCrate(state) => {
-                                    match state {
-                                        InsertState::Instructions(coordinates) => {
-                                            let mut crate_item = context
-                                                .level
-                                                .get_crate_from_level(&coordinates)
-                                                .clone();
-                                            if crate_item.crate_type > 0 {
-                                                crate_item.crate_type = crate_item.crate_type - 1;
-                                                context
-                                                    .level
-                                                    .put_crate_to_level(coordinates, &crate_item);
-                                            }
-                                        }
-                                        _ => (),
+                                }
+                                InsertType::NormalCrate(InsertState::Instructions(coordinates))
+                                | InsertType::DMCrate(InsertState::Instructions(coordinates)) => {
+                                    let mut crate_item =
+                                        context.level.get_crate_from_level(&coordinates).clone();
+                                    let previous_class = (crate_item.crate_class as u32)
+                                        .checked_sub(1)
+                                        .and_then(CrateClass::from_u32);
+                                    if let Some(crate_class) = previous_class {
+                                        crate_item.crate_type = 0;
+                                        crate_item.crate_class = crate_class;
+                                        context.level.put_crate_to_level(&coordinates, &crate_item)
                                     }
                                 }
-                                _ => {
-                                    if context.level.scroll.0 > 0 {
-                                        context.level.scroll.0 = context.level.scroll.0 - 1;
+                                _ => (),
+                            },
+                            Keycode::Left => match &insert_item {
+                                InsertType::Steam(InsertState::Instructions(coordinates)) => {
+                                    let steam = context.level.get_steam_from_level(&coordinates);
+                                    context.level.put_steam_to_level(
+                                        &coordinates,
+                                        &Steam {
+                                            angle: (steam.angle + 360 - 5) % 360,
+                                            range: steam.range,
+                                        },
+                                    )
+                                }
+                                InsertType::NormalCrate(InsertState::Instructions(coordinates))
+                                | InsertType::DMCrate(InsertState::Instructions(coordinates)) => {
+                                    let mut crate_item =
+                                        context.level.get_crate_from_level(&coordinates).clone();
+                                    if crate_item.crate_type > 0 {
+                                        crate_item.crate_type = crate_item.crate_type - 1;
+                                        context.level.put_crate_to_level(coordinates, &crate_item);
                                     }
                                 }
+                                _ => (),
                             },
                             Keycode::Right => match &insert_item {
-                                InsertType::Steam(state) => match state {
-                                    InsertState::Instructions(coordinates) => {
-                                        let steam =
-                                            context.level.get_steam_from_level(&coordinates);
-                                        context.level.put_steam_to_level(
-                                            &coordinates,
-                                            &Steam {
-                                                angle: (steam.angle + 5) % 360,
-                                                range: steam.range,
-                                            },
-                                        )
-                                    }
-                                    _ => (),
-                                },
-                                InsertType::NormalCrate(state) | InsertType::DMCrate(state) => {
-                                    match state {
-                                        InsertState::Instructions(coordinates) => {
-                                            let mut crate_item = context
-                                                .level
-                                                .get_crate_from_level(&coordinates)
-                                                .clone();
-                                            if crate_item.crate_type
-                                                < (crates[crate_item.crate_class as usize].len()
-                                                    - 1)
-                                                    as u8
-                                            {
-                                                crate_item.crate_type = crate_item.crate_type + 1;
-                                                context
-                                                    .level
-                                                    .put_crate_to_level(coordinates, &crate_item);
-                                            }
-                                        }
-                                        _ => (),
-                                    }
+                                InsertType::Steam(InsertState::Instructions(coordinates)) => {
+                                    let steam = context.level.get_steam_from_level(&coordinates);
+                                    context.level.put_steam_to_level(
+                                        &coordinates,
+                                        &Steam {
+                                            angle: (steam.angle + 5) % 360,
+                                            range: steam.range,
+                                        },
+                                    )
                                 }
-                                _ => {
-                                    if context.level.scroll.0
-                                        + context.graphics.get_full_x_tiles_per_screen()
-                                        < (context.level.tiles[0].len()) as u32
+                                InsertType::NormalCrate(InsertState::Instructions(coordinates))
+                                | InsertType::DMCrate(InsertState::Instructions(coordinates)) => {
+                                    let mut crate_item =
+                                        context.level.get_crate_from_level(&coordinates).clone();
+                                    if crate_item.crate_type
+                                        < (crates[crate_item.crate_class as usize].len() - 1) as u8
                                     {
-                                        context.level.scroll.0 = context.level.scroll.0 + 1;
+                                        crate_item.crate_type = crate_item.crate_type + 1;
+                                        context.level.put_crate_to_level(coordinates, &crate_item);
                                     }
                                 }
+                                _ => (),
                             },
                             Keycode::Return | Keycode::KpEnter => {
                                 if matches!(
@@ -462,6 +1025,13 @@ pub fn exec(context: &mut Context) -> NextMode {
                                     && new_level_size_x.parse::<u8>().unwrap() >= 16
                                 {
                                     prompt = PromptType::NewLevel(NewLevelState::YSize);
+                                } else if prompt == PromptType::NewLevel(NewLevelState::YSize)
+                                    && new_level_size_x.len() > 1
+                                    && new_level_size_y.parse::<u8>().unwrap() >= 12
+                                    && generate_new_level
+                                {
+                                    generate_fields = GenerateFields::new();
+                                    prompt = PromptType::Generate;
                                 } else if prompt == PromptType::NewLevel(NewLevelState::YSize)
                                     && new_level_size_x.len() > 1
                                     && new_level_size_y.parse::<u8>().unwrap() >= 12
@@ -473,6 +1043,7 @@ pub fn exec(context: &mut Context) -> NextMode {
                                     context.sdl.video().unwrap().text_input().stop();
                                     context.textures.saved_level_name = None;
                                     context.level_save_name.clear();
+                                    context.history.clear();
                                     prompt = PromptType::None;
                                 } else if prompt == PromptType::Save(SaveLevelType::NameInput)
                                     && context.level_save_name.len() > 1
@@ -481,15 +1052,31 @@ pub fn exec(context: &mut Context) -> NextMode {
                                         context.level_save_name.to_uppercase();
                                     let level_saved_name =
                                         format!("{}.LEV", &level_save_name_uppercase);
-                                    context.level.serialize(&level_saved_name).unwrap();
-                                    context.sdl.video().unwrap().text_input().stop();
-                                    context.textures.saved_level_name = Some(create_text_texture(
-                                        &mut context.canvas,
-                                        &context.texture_creator,
-                                        &context.font,
-                                        &level_saved_name.clone().to_lowercase(),
-                                    ));
-                                    prompt = PromptType::None;
+                                    match context
+                                        .level
+                                        .serialize_as_version(&level_saved_name, save_version)
+                                    {
+                                        Ok(()) => {
+                                            context.sdl.video().unwrap().text_input().stop();
+                                            context.textures.saved_level_name =
+                                                Some(create_text_texture(
+                                                    &mut context.canvas,
+                                                    context.texture_creator,
+                                                    &context.font,
+                                                    &level_saved_name.to_lowercase(),
+                                                ));
+                                            save_error_texture = None;
+                                            prompt = PromptType::None;
+                                        }
+                                        Err(error) => {
+                                            save_error_texture = Some(create_text_texture(
+                                                &mut context.canvas,
+                                                context.texture_creator,
+                                                &context.font,
+                                                &format!("save failed: {}", error),
+                                            ));
+                                        }
+                                    }
                                 }
                             }
                             Keycode::Backspace => match &prompt {
@@ -510,17 +1097,28 @@ pub fn exec(context: &mut Context) -> NextMode {
                                 },
                                 _ => (),
                             },
-                            Keycode::Plus | Keycode::KpPlus => {
-                                if context.graphics.render_multiplier == 1 {
-                                    context.graphics.render_multiplier = 2;
+                            Keycode::PageUp => {
+                                if prompt == PromptType::Save(SaveLevelType::NameInput)
+                                    && save_version < VERSION
+                                {
+                                    save_version += 1;
                                 }
                             }
-                            Keycode::Minus | Keycode::KpMinus => {
-                                if context.graphics.render_multiplier == 2 {
-                                    context.graphics.render_multiplier = 1;
-                                    context.level.scroll = (0, 0);
+                            Keycode::PageDown => {
+                                if prompt == PromptType::Save(SaveLevelType::NameInput)
+                                    && save_version > OLDEST_VERSION
+                                {
+                                    save_version -= 1;
                                 }
                             }
+                            Keycode::Plus | Keycode::KpPlus => {
+                                zoom(context, 1);
+                                status_text_texture = Some(create_zoom_text_texture(context));
+                            }
+                            Keycode::Minus | Keycode::KpMinus => {
+                                zoom(context, -1);
+                                status_text_texture = Some(create_zoom_text_texture(context));
+                            }
                             _ => {
                                 if prompt != PromptType::NewLevel(NewLevelState::XSize)
                                     && prompt != PromptType::NewLevel(NewLevelState::YSize)
@@ -536,7 +1134,31 @@ pub fn exec(context: &mut Context) -> NextMode {
                     if x >= 0 && y >= 0 {
                         context.mouse.0 = x as u32;
                         context.mouse.1 = y as u32;
-                        if mouse_left_click.is_some() {
+                        if let Some((start_mouse, start_scroll)) = panning {
+                            context.level.scroll = start_scroll;
+                            scroll_by(
+                                context,
+                                (start_mouse.0 as i32 - x, start_mouse.1 as i32 - y),
+                            );
+                        }
+                        if dragging_minimap {
+                            if let Some(tile) = get_hovered_minimap_tile(context) {
+                                center_viewport(context, &tile);
+                            }
+                        } else if let Some(anchor) = selection_anchor {
+                            selection = Some(Area::from_corners(
+                                &anchor,
+                                &get_hovered_level_tile(context),
+                            ));
+                        } else if mouse_left_click.is_some()
+                            && matches!(tool, Tool::Brush | Tool::Eraser)
+                        {
+                            paint_with_brush(context, tool == Tool::Eraser);
+                        } else if mouse_left_click.is_some() && tool == Tool::Move {
+                            if let Some(point) = &mut drag_point {
+                                drag_objects(context, &mut selected_objects, point);
+                            }
+                        } else if mouse_left_click.is_some() && shape_start.is_none() {
                             handle_mouse_left_down(
                                 context,
                                 &mut set_position,
@@ -553,54 +1175,175 @@ pub fn exec(context: &mut Context) -> NextMode {
                     mouse_btn: MouseButton::Left,
                     ..
                 } => {
-                    mouse_left_click = Some(context.mouse);
-                    handle_mouse_left_down(
-                        context,
-                        &mut set_position,
-                        &mut insert_item,
-                        &mut drag_tiles,
-                    );
+                    let shift_held = context
+                        .sdl
+                        .keyboard()
+                        .mod_state()
+                        .intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+                    if let Some(tile) = get_hovered_minimap_tile(context) {
+                        dragging_minimap = true;
+                        center_viewport(context, &tile);
+                    } else if pasting {
+                        if let Some(clipboard) = &context.clipboard {
+                            let (x, y) = get_hovered_level_tile(context);
+                            context.level.paste(clipboard, &(x, y));
+                            let (width, height) = clipboard.get_size();
+                            update_walls_and_shadows(
+                                context,
+                                &Area {
+                                    x,
+                                    y,
+                                    width,
+                                    height,
+                                },
+                            );
+                        }
+                        pasting = false;
+                    } else if tool == Tool::Fill
+                        && insert_item == InsertType::None
+                        && set_position == 0
+                    {
+                        let position = get_hovered_level_tile(context);
+                        let changed = match &context.variation_brush {
+                            Some(brush) => {
+                                let region =
+                                    context.level.get_fill_region(&position, &fill_options);
+                                context.level.put_random_tiles(&region, brush)
+                            }
+                            None => context.level.flood_fill(
+                                &position,
+                                context.selected_tile_id,
+                                &context.texture_type_selected,
+                                &fill_options,
+                            ),
+                        };
+                        if let Some(area) = changed {
+                            finish_tile_changes(context, &area);
+                        }
+                    } else if matches!(tool, Tool::Shape(_))
+                        && insert_item == InsertType::None
+                        && set_position == 0
+                    {
+                        shape_start = Some(get_hovered_level_tile(context));
+                        mouse_left_click = Some(get_hovered_level_tile(context));
+                    } else if matches!(tool, Tool::Brush | Tool::Eraser)
+                        && insert_item == InsertType::None
+                        && set_position == 0
+                    {
+                        mouse_left_click = Some(get_hovered_level_tile(context));
+                        paint_with_brush(context, tool == Tool::Eraser);
+                    } else if tool == Tool::Move
+                        && insert_item == InsertType::None
+                        && set_position == 0
+                    {
+                        let pixel = get_hovered_level_pixel(context);
+                        let clicked = context
+                            .level
+                            .get_objects_at(&pixel, context.graphics.render_multiplier)
+                            .into_iter()
+                            .find(|object| context.layers.shows_object(&context.level, object));
+                        match clicked {
+                            // Shift adds objects to the selection or takes
+                            // them out of it
+                            Some(object) if shift_held => {
+                                if !selected_objects.remove(&object) {
+                                    selected_objects.insert(object);
+                                }
+                            }
+                            Some(object) => {
+                                if !selected_objects.contains(&object) {
+                                    selected_objects = BTreeSet::from([object]);
+                                }
+                                drag_point = Some((pixel.0 as i64, pixel.1 as i64));
+                            }
+                            None => {
+                                if !shift_held {
+                                    selected_objects.clear();
+                                }
+                                box_anchor = Some(pixel);
+                            }
+                        }
+                        mouse_left_click = Some(get_hovered_level_tile(context));
+                    } else if shift_held && insert_item == InsertType::None && set_position == 0 {
+                        let tile = get_hovered_level_tile(context);
+                        selection_anchor = Some(tile);
+                        selection = Some(Area::from_corners(&tile, &tile));
+                        mouse_left_click = Some(get_hovered_level_tile(context));
+                    } else {
+                        mouse_left_click = Some(get_hovered_level_tile(context));
+                        handle_mouse_left_down(
+                            context,
+                            &mut set_position,
+                            &mut insert_item,
+                            &mut drag_tiles,
+                        );
+                    }
                 }
                 Event::MouseButtonUp {
                     mouse_btn: MouseButton::Left,
                     ..
                 } => {
+                    selection_anchor = None;
+                    dragging_minimap = false;
+                    drag_point = None;
+                    if let Some(anchor) = box_anchor.take() {
+                        let corner = get_hovered_level_pixel(context);
+                        selected_objects.extend(
+                            context
+                                .level
+                                .get_objects_in(&anchor, &corner)
+                                .into_iter()
+                                .filter(|object| {
+                                    context.layers.shows_object(&context.level, object)
+                                }),
+                        );
+                    }
+                    if let (Tool::Shape(shape), Some(start)) = (tool, shape_start.take()) {
+                        let end = get_hovered_level_tile(context);
+                        let area = match &context.variation_brush {
+                            Some(brush) => {
+                                let tiles = shape.get_tiles(&start, &end);
+                                context.level.put_random_tiles(&tiles, brush);
+                                context.level.limit_area(&Area::from_corners(&start, &end))
+                            }
+                            None => context.level.draw_shape(
+                                shape,
+                                &start,
+                                &end,
+                                context.selected_tile_id,
+                                &context.texture_type_selected,
+                            ),
+                        };
+                        finish_tile_changes(context, &area);
+                    }
                     if drag_tiles {
                         drag_tiles = false;
-                        if let Some(coordinates) = mouse_left_click {
-                            let selected_level_tiles = get_selected_level_tiles(
-                                &context.graphics,
-                                &get_limited_screen_level_size(
-                                    &context.graphics,
-                                    &coordinates,
-                                    &context.level,
-                                    context.graphics.get_render_size(),
-                                ),
-                                &get_limited_screen_level_size(
-                                    &context.graphics,
-                                    &context.mouse,
-                                    &context.level,
-                                    context.graphics.get_render_size(),
-                                ),
-                                context.level.tiles[0].len() as u32,
-                                Some(context.level.scroll),
-                            );
-                            for level_tile_id in selected_level_tiles {
-                                context.level.put_tile_to_level(
-                                    level_tile_id,
-                                    Some(context.selected_tile_id),
+                        if let Some(start) = mouse_left_click {
+                            let area = Area::from_corners(&start, &get_hovered_level_tile(context));
+                            if let Some(brush) = &context.variation_brush {
+                                context.level.put_random_tiles(&area.get_tiles(), brush);
+                            } else {
+                                let selected_tile_id = context.selected_tile_id;
+                                context.level.put_tiles(
+                                    &area.get_tiles(),
+                                    |_| selected_tile_id,
                                     &context.texture_type_selected,
                                 );
                             }
-                            if context.texture_type_selected == TextureType::SHADOW {
-                                context.automatic_shadows = false;
-                            } else if context.automatic_shadows {
-                                context.level.create_shadows();
-                            }
+                            finish_tile_changes(context, &area);
                         }
                     };
                     mouse_left_click = None;
                 }
+                Event::MouseWheel { y, .. } if matches!(tool, Tool::Brush | Tool::Eraser) => {
+                    context.brush.size =
+                        (context.brush.size as i32 + y).clamp(1, MAX_BRUSH_SIZE as i32) as u32;
+                    status_text_texture = Some(create_brush_text_texture(context));
+                }
+                Event::MouseWheel { y, .. } if prompt == PromptType::None => {
+                    zoom(context, y);
+                    status_text_texture = Some(create_zoom_text_texture(context));
+                }
                 Event::MouseButtonDown {
                     mouse_btn: MouseButton::Right,
                     ..
@@ -614,8 +1357,83 @@ pub fn exec(context: &mut Context) -> NextMode {
                 } => {
                     mouse_right_click = false;
                 }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Middle,
+                    ..
+                } => {
+                    panning = Some((context.mouse, context.level.scroll));
+                }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Middle,
+                    ..
+                } => {
+                    panning = None;
+                }
                 _ => {}
             }
+            // Drags and item adjustments become one undo step when finished
+            if mouse_left_click.is_none() && !mouse_right_click && !is_editing_item(&insert_item) {
+                context.history.commit(&context.level);
+            }
+        }
+        // Undo or cutting may have removed the selected object
+        selected_objects.retain(|object| context.level.contains_object(object));
+        // Arrow keys scroll for as long as they are held, unless they move
+        // the selected object
+        if prompt == PromptType::None
+            && !is_editing_item(&insert_item)
+            && selected_objects.is_empty()
+        {
+            let keyboard = event_pump.keyboard_state();
+            let held = |scancode| keyboard.is_scancode_pressed(scancode) as i32;
+            let direction = (
+                held(Scancode::Right) - held(Scancode::Left),
+                held(Scancode::Down) - held(Scancode::Up),
+            );
+            if direction != (0, 0) {
+                scroll_by(
+                    context,
+                    (
+                        direction.0 * KEY_SCROLL_SPEED,
+                        direction.1 * KEY_SCROLL_SPEED,
+                    ),
+                );
+            }
+        }
+        if context.edge_scrolling && mouse_left_click.is_some() {
+            let direction = get_edge_scroll_direction(&context.graphics, &context.mouse);
+            if direction != (0, 0) {
+                scroll_by(
+                    context,
+                    (
+                        direction.0 * EDGE_SCROLL_SPEED,
+                        direction.1 * EDGE_SCROLL_SPEED,
+                    ),
+                );
+                // The level moves under a cursor that stays still
+                if let Some(anchor) = selection_anchor {
+                    selection = Some(Area::from_corners(
+                        &anchor,
+                        &get_hovered_level_tile(context),
+                    ));
+                } else if matches!(tool, Tool::Brush | Tool::Eraser) {
+                    paint_with_brush(context, tool == Tool::Eraser);
+                } else if let (Tool::Move, Some(point)) = (tool, &mut drag_point) {
+                    drag_objects(context, &mut selected_objects, point);
+                }
+            }
+        }
+        if context.show_lighting {
+            let key = (
+                context.level.spotlights.clone(),
+                get_level_size(&context.level),
+            );
+            if light_texture.as_ref().map(|(old_key, _)| old_key) != Some(&key) {
+                let texture = render::create_light_texture(context.texture_creator, &context.level);
+                light_texture = Some((key, texture));
+            }
+        } else {
+            light_texture = None;
         }
         render::render_level(
             &mut context.canvas,
@@ -623,43 +1441,120 @@ pub fn exec(context: &mut Context) -> NextMode {
             &context.level,
             &context.textures,
             &context.trigonometry,
+            &context.layers,
+            light_texture.as_ref().map(|(_, texture)| texture),
         );
-        let highlighted_id = get_tile_id_from_coordinates(
-            &context.graphics,
-            &get_limited_screen_level_size(
-                &context.graphics,
-                &context.mouse,
-                &context.level,
-                context.graphics.get_render_size(),
-            ),
-            context.graphics.get_x_tiles_per_screen(),
-            None,
-        );
-        render::highlight_selected_tile(
+        if context.show_grid {
+            render::render_grid(&mut context.canvas, &context.graphics, &context.level);
+        }
+        let hovered = get_hovered_level_tile(context);
+        render::highlight_level_area(
             &mut context.canvas,
             &context.graphics,
-            highlighted_id,
+            &context.level.scroll,
+            &Area::from_corners(&hovered, &hovered),
             &render::RendererColor::White,
         );
+        if let Some(area) = &selection {
+            render::highlight_level_area(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level.scroll,
+                area,
+                &render::RendererColor::LightGreen,
+            );
+        }
+        if matches!(tool, Tool::Brush | Tool::Eraser) {
+            let tiles = context.brush.get_tiles(&get_hovered_level_tile(context));
+            render::highlight_level_tiles(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level.scroll,
+                &tiles,
+                if tool == Tool::Eraser {
+                    &render::RendererColor::Red
+                } else {
+                    &render::RendererColor::White
+                },
+            );
+        }
+        for object in &selected_objects {
+            render::highlight_object(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level,
+                object,
+                &render::RendererColor::White,
+            );
+        }
+        if let Some(anchor) = box_anchor {
+            let corner = get_hovered_level_pixel(context);
+            render::highlight_level_box(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level.scroll,
+                &anchor,
+                &corner,
+                &render::RendererColor::LightGreen,
+            );
+        }
+        if let (Tool::Shape(shape), Some(start)) = (tool, shape_start) {
+            let tiles = shape.get_tiles(&start, &get_hovered_level_tile(context));
+            render::highlight_level_tiles(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level.scroll,
+                &tiles,
+                &render::RendererColor::White,
+            );
+        }
+        if let (true, Some(clipboard)) = (pasting, &context.clipboard) {
+            let position = get_hovered_level_tile(context);
+            render::render_clipboard_preview(
+                &mut context.canvas,
+                &context.graphics,
+                &context.level.scroll,
+                &mut context.textures,
+                &context.trigonometry,
+                &context.layers,
+                clipboard,
+                &position,
+            );
+        }
         let render_size = context.graphics.get_render_size();
-        render::render_text_texture(
-            &mut context.canvas,
-            &textures.p1_text_texture,
-            context.level.p1_position.0 * render_size,
-            context.level.p1_position.1 * render_size,
-            render_size,
-            Some(context.level.scroll),
-        );
-        render::render_text_texture(
-            &mut context.canvas,
-            &textures.p2_text_texture,
-            context.level.p2_position.0 * render_size,
-            context.level.p2_position.1 * render_size,
-            render_size,
-            Some(context.level.scroll),
-        );
+        let player_starts = if context.layers.player_starts {
+            vec![
+                (&textures.p1_text_texture, context.level.p1_position),
+                (&textures.p2_text_texture, context.level.p2_position),
+            ]
+        } else {
+            Vec::new()
+        };
+        for (texture, position) in player_starts {
+            let (x, y) = get_screen_coordinates_from_level_coordinates(
+                &context.graphics,
+                &(
+                    position.0 * context.graphics.tile_size,
+                    position.1 * context.graphics.tile_size,
+                ),
+                &context.level.scroll,
+            );
+            // Text can not start left of or above the window
+            if x >= 0 && y >= 0 {
+                render::render_text_texture(
+                    &mut context.canvas,
+                    texture,
+                    x as u32,
+                    y as u32,
+                    render_size,
+                    None,
+                );
+            }
+        }
         let text_position = (8, 8);
-        let text_texture = if set_position == 1 {
+        let text_texture = if pasting {
+            &textures.paste_instructions_text_texture
+        } else if set_position == 1 {
             &textures.p1_set_text_texture
         } else if set_position == 2 {
             &textures.p2_set_text_texture
@@ -694,6 +1589,10 @@ pub fn exec(context: &mut Context) -> NextMode {
             || matches!(insert_item, InsertType::DMCrate(InsertState::Delete))
         {
             &textures.delete_crate_text_texture
+        } else if let Some(texture) = get_tool_instructions(&textures, tool) {
+            texture
+        } else if selection.is_some() {
+            &textures.selection_instructions_text_texture
         } else {
             &textures.help_text_texture
         };
@@ -704,42 +1603,60 @@ pub fn exec(context: &mut Context) -> NextMode {
             render_size,
             None,
         );
+        if prompt == PromptType::Save(SaveLevelType::NameInput) {
+            if loss_textures.as_ref().map(|(version, _)| *version) != Some(save_version) {
+                loss_textures = Some((save_version, create_loss_textures(context, save_version)));
+            }
+        } else {
+            loss_textures = None;
+        }
         render_prompt_if_needed(
             context,
             &textures,
             &prompt,
             &new_level_size_x,
             &new_level_size_y,
+            save_error_texture.as_ref(),
+            save_version,
+            loss_textures.as_ref().map_or(&[], |(_, textures)| textures),
         );
-        if insert_item == InsertType::None {
-            if let Some(coordinates) = mouse_left_click {
-                let selected_screen_tiles = get_selected_level_tiles(
+        if prompt == PromptType::Resize {
+            render_resize_prompt(context, &textures, &resize_fields);
+        }
+        if prompt == PromptType::Generate {
+            render_generate_prompt(context, &textures, &generate_fields);
+        }
+        if insert_item == InsertType::None && shape_start.is_none() && tool != Tool::Move {
+            if let Some(start) = mouse_left_click {
+                let tiles =
+                    Area::from_corners(&start, &get_hovered_level_tile(context)).get_tiles();
+                render::highlight_level_tiles(
+                    &mut context.canvas,
                     &context.graphics,
-                    &get_limited_screen_level_size(
-                        &context.graphics,
-                        &coordinates,
-                        &context.level,
-                        context.graphics.get_render_size(),
-                    ),
-                    &get_limited_screen_level_size(
-                        &context.graphics,
-                        &context.mouse,
-                        &context.level,
-                        context.graphics.get_render_size(),
-                    ),
-                    context.graphics.get_x_tiles_per_screen(),
-                    None,
+                    &context.level.scroll,
+                    &tiles,
+                    &render::RendererColor::White,
                 );
-                for screen_tile_id in selected_screen_tiles {
-                    render::highlight_selected_tile(
-                        &mut context.canvas,
-                        &context.graphics,
-                        screen_tile_id,
-                        &render::RendererColor::White,
-                    );
-                }
             }
         }
+        if context.show_minimap {
+            if minimap_texture.as_ref().map(|(tiles, _)| tiles) != Some(&context.level.tiles) {
+                let texture =
+                    render::create_minimap_texture(context.texture_creator, &context.level);
+                minimap_texture = Some((context.level.tiles.clone(), texture));
+            }
+            if let Some((_, texture)) = &minimap_texture {
+                render::render_minimap(
+                    &mut context.canvas,
+                    &context.graphics,
+                    &context.level,
+                    texture,
+                    get_minimap_rect(&context.graphics, get_level_size(&context.level)),
+                );
+            }
+        } else {
+            minimap_texture = None;
+        }
         if let Some(texture) = &context.textures.saved_level_name {
             render::render_text_texture_coordinates(
                 &mut context.canvas,
@@ -749,16 +1666,459 @@ pub fn exec(context: &mut Context) -> NextMode {
                 None,
             );
         }
+        if let Some(texture) = &status_text_texture {
+            let (x, y) = get_bottom_text_position(context.graphics.resolution_y);
+            render::render_text_texture(&mut context.canvas, texture, x, y - 30, render_size, None);
+        }
+        if prompt == PromptType::None && get_hovered_minimap_tile(context).is_none() {
+            let text = get_hover_text(context);
+            if hover_text.as_ref().map(|(old_text, _)| old_text) != Some(&text) {
+                let texture = create_text_texture(
+                    &mut context.canvas,
+                    context.texture_creator,
+                    &context.font,
+                    &text,
+                );
+                hover_text = Some((text, texture));
+            }
+            if let Some((_, texture)) = &hover_text {
+                let (x, y) = get_bottom_text_position(context.graphics.resolution_y);
+                render::render_text_texture(
+                    &mut context.canvas,
+                    texture,
+                    x,
+                    y - 60,
+                    render_size,
+                    None,
+                );
+            }
+        }
         render::render_and_wait(&mut context.canvas);
     }
 }
 
-fn sanitize_numeric_input(new_text: &str, target_text: &mut String) {
-    if new_text.chars().all(char::is_numeric) && (target_text.len() + new_text.len() <= 3) {
+// Renders the whole level, not just the viewport, next to the level file
+fn save_screenshot(context: &Context) -> String {
+    let name = if context.level_save_name.is_empty() {
+        "LEVEL".to_string()
+    } else {
+        context.level_save_name.to_uppercase()
+    };
+    let filename = format!("{}.PNG", name);
+    // Export at the closest whole scale, at least 1x
+    let options = RenderOptions {
+        scale: (context.graphics.render_multiplier.round() as u32).max(1),
+        lighting: context.show_lighting,
+    };
+    let result = Assets::load("./assets")
+        .map_err(|e| e.to_string())
+        .and_then(|assets| {
+            render_level(&context.level, &assets, &options)
+                .map_err(|e| e.to_string())?
+                .save_png(&filename)
+                .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => format!("image saved to {}", filename.to_lowercase()),
+        Err(error) => format!("image save failed: {}", error),
+    }
+}
+
+// Picks wall pieces and shadows again after the selected tile was painted
+// over `area`
+fn finish_tile_changes(context: &mut Context, area: &Area) {
+    if context.texture_type_selected == TextureType::SHADOW {
+        context.automatic_shadows = false;
+        return;
+    }
+    update_walls_and_shadows(context, area);
+}
+
+fn update_walls_and_shadows(context: &mut Context, area: &Area) {
+    if context.automatic_walls {
+        context.level.auto_tile_area(&context.auto_tile_rules, area);
+    }
+    if context.automatic_shadows {
+        context.level.create_shadows();
+    }
+}
+
+// Paints the selected tile, or erases, under the brush at the mouse
+fn paint_with_brush(context: &mut Context, erase: bool) {
+    let tiles = context.brush.get_tiles(&get_hovered_level_tile(context));
+    let selected_tile_id = context.selected_tile_id;
+    let changed = if erase {
+        context
+            .level
+            .erase(&tiles, &context.eraser_tile, context.eraser_removes_objects)
+    } else if let Some(brush) = &context.variation_brush {
+        context.level.put_random_tiles(&tiles, brush)
+    } else {
+        context
+            .level
+            .put_tiles(&tiles, |_| selected_tile_id, &context.texture_type_selected)
+    };
+    match changed {
+        Some(area) if erase => update_walls_and_shadows(context, &area),
+        Some(area) => finish_tile_changes(context, &area),
+        None => (),
+    }
+}
+
+fn get_tool_instructions<'a>(textures: &'a EditorTextures, tool: Tool) -> Option<&'a Texture<'a>> {
+    match tool {
+        Tool::None => None,
+        Tool::Fill => Some(&textures.fill_instructions_text_texture),
+        Tool::Shape(Shape::Line) => Some(&textures.line_instructions_text_texture),
+        Tool::Shape(Shape::Rectangle) => Some(&textures.rectangle_instructions_text_texture),
+        Tool::Shape(Shape::Ellipse) => Some(&textures.ellipse_instructions_text_texture),
+        Tool::Brush => Some(&textures.brush_instructions_text_texture),
+        Tool::Eraser => Some(&textures.eraser_instructions_text_texture),
+        Tool::Move => Some(&textures.move_instructions_text_texture),
+    }
+}
+
+fn create_loss_textures<'a>(context: &mut Context<'a>, version: u32) -> Vec<Texture<'a>> {
+    context
+        .level
+        .get_version_losses(version)
+        .iter()
+        .map(|loss| {
+            create_text_texture(
+                &mut context.canvas,
+                context.texture_creator,
+                &context.font,
+                &format!("- {}", loss),
+            )
+        })
+        .collect()
+}
+
+fn create_brush_text_texture<'a>(context: &mut Context<'a>) -> Texture<'a> {
+    let size = context.brush.size;
+    create_text_texture(
+        &mut context.canvas,
+        context.texture_creator,
+        &context.font,
+        &format!(
+            "{} brush {}x{}",
+            match context.brush.shape {
+                BrushShape::Square => "square",
+                BrushShape::Round => "round",
+            },
+            size,
+            size
+        ),
+    )
+}
+
+fn create_zoom_text_texture<'a>(context: &mut Context<'a>) -> Texture<'a> {
+    let zoom = context.graphics.render_multiplier;
+    create_text_texture(
+        &mut context.canvas,
+        context.texture_creator,
+        &context.font,
+        &format!("zoom {}x", zoom),
+    )
+}
+
+// Zooms in or out keeping the level tile under the mouse cursor in place
+fn zoom(context: &mut Context, steps: i32) {
+    let mouse = context.mouse;
+    let hovered = get_level_coordinates_from_screen_coordinates(
+        &context.graphics,
+        &mouse,
+        &context.level.scroll,
+    );
+    context.graphics.zoom(steps);
+    let on_screen =
+        get_level_coordinates_from_screen_coordinates(&context.graphics, &mouse, &(0, 0));
+    context.level.scroll = (
+        hovered.0.saturating_sub(on_screen.0),
+        hovered.1.saturating_sub(on_screen.1),
+    );
+    limit_scroll(context);
+}
+
+// Input that only moves the view or changes editor settings, so there is
+// nothing to undo and no need to start an undo step
+fn is_view_input(event: &Event, context: &Context, arrows_edit: bool) -> bool {
+    match event {
+        Event::MouseButtonDown {
+            mouse_btn: MouseButton::Middle,
+            ..
+        } => true,
+        Event::MouseButtonDown {
+            mouse_btn: MouseButton::Left,
+            ..
+        } => get_hovered_minimap_tile(context).is_some(),
+        Event::KeyDown {
+            keycode: Some(key),
+            keymod,
+            ..
+        } => {
+            if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+                && (matches!(key, Keycode::G | Keycode::L) || get_layer_index(*key).is_some())
+            {
+                return true;
+            }
+            match key {
+                Keycode::Up | Keycode::Down | Keycode::Left | Keycode::Right => !arrows_edit,
+                Keycode::Space
+                | Keycode::F1
+                | Keycode::F10
+                | Keycode::F11
+                | Keycode::M
+                | Keycode::T
+                | Keycode::Plus
+                | Keycode::KpPlus
+                | Keycode::Minus
+                | Keycode::KpMinus
+                | Keycode::PageUp
+                | Keycode::PageDown => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// Alt with 1-8 shows or hides one of the layers
+fn get_layer_index(key: Keycode) -> Option<usize> {
+    [
+        Keycode::Num1,
+        Keycode::Num2,
+        Keycode::Num3,
+        Keycode::Num4,
+        Keycode::Num5,
+        Keycode::Num6,
+        Keycode::Num7,
+        Keycode::Num8,
+    ]
+    .iter()
+    .position(|layer_key| *layer_key == key)
+}
+
+// Tile under the cursor and the topmost shown object there
+fn get_hover_text(context: &Context) -> String {
+    let (x, y) = get_hovered_level_tile(context);
+    let tile = context.level.tiles[y as usize][x as usize];
+    let mut text = format!(
+        "tile {},{} {} {}, shadow {}",
+        x,
+        y,
+        match tile.texture_type {
+            TextureType::FLOOR => "floor",
+            TextureType::WALLS => "wall",
+            TextureType::SHADOW => "shadow",
+        },
+        tile.id,
+        tile.shadow,
+    );
+    let hovered = context
+        .level
+        .get_objects_at(
+            &get_hovered_level_pixel(context),
+            context.graphics.render_multiplier,
+        )
+        .into_iter()
+        .find(|object| context.layers.shows_object(&context.level, object));
+    if let Some(object) = hovered {
+        let kind = match object.kind {
+            ObjectKind::Spotlight => "spotlight",
+            ObjectKind::Steam => "steam",
+            ObjectKind::Crate => "crate",
+        };
+        text += &format!(" - {} {},{}", kind, object.position.0, object.position.1);
+    }
+    text
+}
+
+fn get_hovered_level_pixel(context: &Context) -> (u32, u32) {
+    let limited = get_limited_screen_level_size(&context.graphics, &context.mouse, &context.level);
+    get_level_coordinates_from_screen_coordinates(
+        &context.graphics,
+        &limited,
+        &context.level.scroll,
+    )
+}
+
+// Moves the objects along with the cursor from the level pixel `point`
+// they are held at, where the level allows
+fn drag_objects(context: &mut Context, objects: &mut BTreeSet<ObjectRef>, point: &mut (i64, i64)) {
+    let pixel = get_hovered_level_pixel(context);
+    let delta = (pixel.0 as i64 - point.0, pixel.1 as i64 - point.1);
+    if let Some(moved) = move_objects_by(context, objects, delta) {
+        *point = (point.0 + moved.0, point.1 + moved.1);
+    }
+}
+
+// Moves the objects `delta` level pixels, stopping at the level edges.
+// Returns how far they moved.
+fn move_objects_by(
+    context: &mut Context,
+    objects: &mut BTreeSet<ObjectRef>,
+    delta: (i64, i64),
+) -> Option<(i64, i64)> {
+    let level_pixels = get_level_pixel_size(&context.graphics, get_level_size(&context.level));
+    let positions = || objects.iter().map(|object| object.position);
+    let first = (
+        positions().map(|p| p.0).min()?,
+        positions().map(|p| p.1).min()?,
+    );
+    let last = (
+        positions().map(|p| p.0).max()?,
+        positions().map(|p| p.1).max()?,
+    );
+    let delta = (
+        delta
+            .0
+            .max(-(first.0 as i64))
+            .min(level_pixels.0 as i64 - 1 - last.0 as i64),
+        delta
+            .1
+            .max(-(first.1 as i64))
+            .min(level_pixels.1 as i64 - 1 - last.1 as i64),
+    );
+    let moved = context.level.move_objects(objects, delta)?;
+    *objects = moved;
+    Some(delta)
+}
+
+fn create_objects_text_texture<'a>(
+    context: &mut Context<'a>,
+    count: usize,
+    done: &str,
+) -> Texture<'a> {
+    create_text_texture(
+        &mut context.canvas,
+        context.texture_creator,
+        &context.font,
+        &format!("{} objects {}", count, done),
+    )
+}
+
+fn get_hovered_level_tile(context: &Context) -> (u32, u32) {
+    get_level_tile_at(context, &context.mouse)
+}
+
+fn get_level_tile_at(context: &Context, screen_coordinates: &(u32, u32)) -> (u32, u32) {
+    let limited =
+        get_limited_screen_level_size(&context.graphics, screen_coordinates, &context.level);
+    get_logical_coordinates(
+        &context.graphics,
+        limited.0,
+        limited.1,
+        Some(context.level.scroll),
+    )
+}
+
+// Tiles along the edges that were added or cropped, in the resized level
+fn get_resized_edges(resize: &Resize, size: (u32, u32)) -> Option<Area> {
+    // Cropped edges only change the pieces of the new border tiles
+    let depth = |amount: i32| amount.max(1) as u32;
+    let mut corners = Vec::new();
+    if resize.left != 0 {
+        corners.extend([(0, 0), (depth(resize.left) - 1, size.1 - 1)]);
+    }
+    if resize.right != 0 {
+        corners.extend([(size.0 - depth(resize.right), 0), (size.0 - 1, size.1 - 1)]);
+    }
+    if resize.top != 0 {
+        corners.extend([(0, 0), (size.0 - 1, depth(resize.top) - 1)]);
+    }
+    if resize.bottom != 0 {
+        corners.extend([(0, size.1 - depth(resize.bottom)), (size.0 - 1, size.1 - 1)]);
+    }
+    Area::from_tiles(corners)
+}
+
+// Pads with the selected floor or wall tile
+fn get_resize_fill_tile(context: &Context) -> Tile {
+    match context.texture_type_selected {
+        TextureType::SHADOW => DEFAULT_FILL_TILE,
+        texture_type => Tile {
+            texture_type,
+            id: context.selected_tile_id,
+            shadow: 0,
+        },
+    }
+}
+
+fn get_level_size(level: &Level) -> (u32, u32) {
+    (level.tiles[0].len() as u32, level.tiles.len() as u32)
+}
+
+fn get_hovered_minimap_tile(context: &Context) -> Option<(u32, u32)> {
+    if !context.show_minimap {
+        return None;
+    }
+    let level_size = get_level_size(&context.level);
+    let rect = get_minimap_rect(&context.graphics, level_size);
+    get_minimap_tile(&rect, level_size, &context.mouse)
+}
+
+// Scrolls so that `tile` is in the middle of the window if possible
+fn center_viewport(context: &mut Context, tile: &(u32, u32)) {
+    let tile_size = context.graphics.tile_size;
+    let viewport = get_viewport_pixel_size(&context.graphics);
+    context.level.scroll = (
+        (tile.0 * tile_size + tile_size / 2).saturating_sub(viewport.0 / 2),
+        (tile.1 * tile_size + tile_size / 2).saturating_sub(viewport.1 / 2),
+    );
+    limit_scroll(context);
+}
+
+// Moves the viewport by `delta` screen pixels
+fn scroll_by(context: &mut Context, delta: (i32, i32)) {
+    let render_size = context.graphics.get_render_size() as i32;
+    let tile_size = context.graphics.tile_size as i32;
+    context.level.scroll = (
+        (context.level.scroll.0 as i32 + delta.0 * tile_size / render_size).max(0) as u32,
+        (context.level.scroll.1 as i32 + delta.1 * tile_size / render_size).max(0) as u32,
+    );
+    limit_scroll(context);
+}
+
+// Keeps the viewport inside a level that may have become smaller
+fn limit_scroll(context: &mut Context) {
+    let level_pixels = get_level_pixel_size(&context.graphics, get_level_size(&context.level));
+    let viewport = get_viewport_pixel_size(&context.graphics);
+    let max_scroll = (
+        level_pixels.0.saturating_sub(viewport.0),
+        level_pixels.1.saturating_sub(viewport.1),
+    );
+    context.level.scroll.0 = context.level.scroll.0.min(max_scroll.0);
+    context.level.scroll.1 = context.level.scroll.1.min(max_scroll.1);
+}
+
+fn is_editing_item(insert_item: &InsertType) -> bool {
+    matches!(
+        insert_item,
+        InsertType::Spotlight(InsertState::Instructions(_))
+            | InsertType::Steam(InsertState::Instructions(_))
+            | InsertType::NormalCrate(InsertState::Instructions(_))
+            | InsertType::DMCrate(InsertState::Instructions(_))
+    )
+}
+
+fn sanitize_numeric_input(new_text: &str, target_text: &mut String, max_length: usize) {
+    if new_text.chars().all(char::is_numeric) && (target_text.len() + new_text.len() <= max_length)
+    {
         *target_text += new_text;
     }
 }
 
+// Digits with an optional leading minus sign
+fn sanitize_signed_input(new_text: &str, target_text: &mut String) {
+    for character in new_text.chars() {
+        let is_valid = character.is_ascii_digit() || (character == '-' && target_text.is_empty());
+        if is_valid && target_text.len() < 4 {
+            target_text.push(character);
+        }
+    }
+}
+
 fn sanitize_level_name_input(new_text: &str, target_text: &mut String) {
     if new_text.chars().all(char::is_alphanumeric) && (target_text.len() + new_text.len() <= 11) {
         *target_text += new_text;
@@ -801,20 +2161,36 @@ fn render_input_prompt(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_prompt_if_needed(
     context: &mut Context,
     textures: &EditorTextures,
     prompt: &PromptType,
     new_level_size_x: &str,
     new_level_size_y: &str,
+    save_error_texture: Option<&Texture>,
+    save_version: u32,
+    loss_textures: &[Texture],
 ) {
-    if *prompt != PromptType::None {
+    if !matches!(
+        prompt,
+        PromptType::None | PromptType::Resize | PromptType::Generate
+    ) {
         let prompt_position = (context.graphics.resolution_x / 2 - 100, 200);
         let prompt_line_spacing = 30;
         let prompt_texture = match &prompt {
             PromptType::NewLevel(state) => {
                 match state {
-                    NewLevelState::Prompt => (),
+                    NewLevelState::Prompt => {
+                        render::render_text_texture(
+                            &mut context.canvas,
+                            &textures.new_level_generate_text_texture,
+                            prompt_position.0,
+                            prompt_position.1 + 2 * prompt_line_spacing,
+                            context.graphics.get_render_size(),
+                            None,
+                        );
+                    }
                     input_state => {
                         if *input_state == NewLevelState::XSize
                             || *input_state == NewLevelState::YSize
@@ -852,6 +2228,47 @@ fn render_prompt_if_needed(
                             &textures.filename_text_texture,
                             &level_save_name,
                         );
+                        render_input_prompt(
+                            context,
+                            (prompt_position.0, prompt_position.1 + prompt_line_spacing),
+                            prompt_line_spacing,
+                            &textures.save_version_text_texture,
+                            &save_version.to_string(),
+                        );
+                        let render_size = context.graphics.get_render_size();
+                        let mut line_position = prompt_position.1 + 4 * prompt_line_spacing;
+                        if let Some(texture) = save_error_texture {
+                            render::render_text_texture(
+                                &mut context.canvas,
+                                texture,
+                                prompt_position.0,
+                                line_position,
+                                render_size,
+                                None,
+                            );
+                            line_position += prompt_line_spacing;
+                        }
+                        if !loss_textures.is_empty() {
+                            render::render_text_texture(
+                                &mut context.canvas,
+                                &textures.save_version_losses_text_texture,
+                                prompt_position.0,
+                                line_position,
+                                render_size,
+                                None,
+                            );
+                        }
+                        for loss_texture in loss_textures {
+                            line_position += 20;
+                            render::render_text_texture(
+                                &mut context.canvas,
+                                loss_texture,
+                                prompt_position.0,
+                                line_position,
+                                render_size,
+                                None,
+                            );
+                        }
                     }
                 };
                 &textures.save_level_text_texture
@@ -865,7 +2282,7 @@ fn render_prompt_if_needed(
                     &textures.create_shadows_disabled_instructions_text_texture
                 }
             },
-            PromptType::None => unreachable!(),
+            PromptType::None | PromptType::Resize | PromptType::Generate => unreachable!(),
         };
         let render_size = context.graphics.get_render_size();
         render::render_text_texture(
@@ -887,6 +2304,123 @@ fn render_prompt_if_needed(
     }
 }
 
+// Title and instruction lines, then one input line per field with the
+// selected one marked, then a line telling the result
+fn render_prompt_fields(
+    context: &mut Context,
+    title_textures: [&Texture; 2],
+    fields: &[(&Texture, &str)],
+    selected: usize,
+    result: &str,
+) {
+    let prompt_position = (context.graphics.resolution_x / 2 - 100, 200);
+    let prompt_line_spacing = 30;
+    let render_size = context.graphics.get_render_size();
+    for (line, texture) in title_textures.into_iter().enumerate() {
+        render::render_text_texture(
+            &mut context.canvas,
+            texture,
+            prompt_position.0,
+            prompt_position.1 + line as u32 * prompt_line_spacing,
+            render_size,
+            None,
+        );
+    }
+    for (index, (texture, value)) in fields.iter().enumerate() {
+        let line_position = (
+            prompt_position.0,
+            prompt_position.1 + index as u32 * prompt_line_spacing,
+        );
+        render_input_prompt(context, line_position, prompt_line_spacing, texture, value);
+        if index == selected {
+            render::render_text_texture(
+                &mut context.canvas,
+                &context.textures.selected_icon,
+                line_position.0 - 20,
+                line_position.1 + 2 * prompt_line_spacing,
+                render_size,
+                None,
+            );
+        }
+    }
+
+    if !result.is_empty() {
+        let result_text_texture = create_text_texture(
+            &mut context.canvas,
+            context.texture_creator,
+            &context.font,
+            result,
+        );
+        render::render_text_texture(
+            &mut context.canvas,
+            &result_text_texture,
+            prompt_position.0,
+            prompt_position.1 + (fields.len() as u32 + 3) * prompt_line_spacing,
+            render_size,
+            None,
+        );
+    }
+}
+
+fn render_resize_prompt(context: &mut Context, textures: &EditorTextures, fields: &ResizeFields) {
+    let resize = fields.get_resize();
+    let result = match context.level.get_resize_report(&resize) {
+        Ok(report) => format!(
+            "new size {}x{}, {}",
+            context.level.tiles[0].len() as i32 + resize.left + resize.right,
+            context.level.tiles.len() as i32 + resize.top + resize.bottom,
+            report
+        ),
+        Err(error) => error.to_string(),
+    };
+    render_prompt_fields(
+        context,
+        [
+            &textures.resize_level_text_texture,
+            &textures.resize_instructions_text_texture,
+        ],
+        &[
+            (&textures.resize_left_text_texture, &fields.edges[0]),
+            (&textures.resize_right_text_texture, &fields.edges[1]),
+            (&textures.resize_top_text_texture, &fields.edges[2]),
+            (&textures.resize_bottom_text_texture, &fields.edges[3]),
+        ],
+        fields.selected,
+        &result,
+    );
+}
+
+fn render_generate_prompt(
+    context: &mut Context,
+    textures: &EditorTextures,
+    fields: &GenerateFields,
+) {
+    let yes_no = |value| if value { "yes" } else { "no" };
+    render_prompt_fields(
+        context,
+        [
+            &textures.generate_level_text_texture,
+            &textures.generate_instructions_text_texture,
+        ],
+        &[
+            (&textures.generate_seed_text_texture, &fields.numbers[0]),
+            (&textures.generate_rooms_text_texture, &fields.numbers[1]),
+            (&textures.generate_corridor_text_texture, &fields.numbers[2]),
+            (&textures.generate_wall_text_texture, &fields.numbers[3]),
+            (
+                &textures.generate_spotlights_text_texture,
+                yes_no(fields.spotlights),
+            ),
+            (
+                &textures.generate_crates_text_texture,
+                yes_no(fields.crates),
+            ),
+        ],
+        fields.selected,
+        fields.error.as_deref().unwrap_or(""),
+    );
+}
+
 fn handle_mouse_left_down(
     context: &mut Context,
     set_position: &mut u8,
@@ -898,18 +2432,23 @@ fn handle_mouse_left_down(
     }
 
     if *set_position > 0 {
-        let position = if *set_position == 1 {
-            &mut context.level.p1_position
-        } else {
-            &mut context.level.p2_position
-        };
-        *position = get_logical_coordinates(
+        let tile = get_logical_coordinates(
             &context.graphics,
             context.mouse.0,
             context.mouse.1,
             Some(context.level.scroll),
         );
-        *set_position = 0;
+        // Clicks outside the level keep waiting for a place in it
+        let level_size = get_level_size(&context.level);
+        if tile.0 < level_size.0 && tile.1 < level_size.1 {
+            let position = if *set_position == 1 {
+                &mut context.level.p1_position
+            } else {
+                &mut context.level.p2_position
+            };
+            *position = tile;
+            *set_position = 0;
+        }
     } else {
         let level_coordinates = get_level_coordinates_from_screen_coordinates(
             &context.graphics,
@@ -965,12 +2504,7 @@ fn handle_mouse_left_down(
 fn handle_mouse_right_down(context: &mut Context) {
     let pointed_tile = get_tile_id_from_coordinates(
         &context.graphics,
-        &get_limited_screen_level_size(
-            &context.graphics,
-            &context.mouse,
-            &context.level,
-            context.graphics.get_render_size(),
-        ),
+        &get_limited_screen_level_size(&context.graphics, &context.mouse, &context.level),
         context.level.tiles[0].len() as u32,
         Some(context.level.scroll),
     );
@@ -984,18 +2518,16 @@ fn get_limited_screen_level_size(
     graphics: &Graphics,
     mouse: &(u32, u32),
     level: &Level,
-    render_size: u32,
 ) -> (u32, u32) {
+    let level_end = get_screen_coordinates_from_level_coordinates(
+        graphics,
+        &get_level_pixel_size(graphics, get_level_size(level)),
+        &level.scroll,
+    );
     limit_coordinates(
         &(
-            std::cmp::min(
-                mouse.0,
-                (level.tiles[0].len() as u32 - level.scroll.0) * render_size - 1,
-            ),
-            std::cmp::min(
-                mouse.1,
-                (level.tiles.len() as u32 - level.scroll.1) * render_size - 1,
-            ),
+            std::cmp::min(mouse.0, (level_end.0 - 1).max(0) as u32),
+            std::cmp::min(mouse.1, (level_end.1 - 1).max(0) as u32),
         ),
         &(graphics.resolution_x, graphics.resolution_y),
     )
@@ -12,6 +12,8 @@ pub struct EditorTextures<'a> {
     pub wanna_quit_text_texture: Texture<'a>,
     pub save_level_text_texture: Texture<'a>,
     pub filename_text_texture: Texture<'a>,
+    pub save_version_text_texture: Texture<'a>,
+    pub save_version_losses_text_texture: Texture<'a>,
     pub press_y_text_texture: Texture<'a>,
     pub new_level_x_size_text_texture: Texture<'a>,
     pub new_level_y_size_text_texture: Texture<'a>,
@@ -27,6 +29,30 @@ pub struct EditorTextures<'a> {
     pub place_deathmatch_create_text_texture: Texture<'a>,
     pub insert_crate_text_texture: Texture<'a>,
     pub delete_crate_text_texture: Texture<'a>,
+    pub selection_instructions_text_texture: Texture<'a>,
+    pub paste_instructions_text_texture: Texture<'a>,
+    pub fill_instructions_text_texture: Texture<'a>,
+    pub line_instructions_text_texture: Texture<'a>,
+    pub rectangle_instructions_text_texture: Texture<'a>,
+    pub ellipse_instructions_text_texture: Texture<'a>,
+    pub brush_instructions_text_texture: Texture<'a>,
+    pub eraser_instructions_text_texture: Texture<'a>,
+    pub move_instructions_text_texture: Texture<'a>,
+    pub resize_level_text_texture: Texture<'a>,
+    pub resize_instructions_text_texture: Texture<'a>,
+    pub resize_left_text_texture: Texture<'a>,
+    pub resize_right_text_texture: Texture<'a>,
+    pub resize_top_text_texture: Texture<'a>,
+    pub resize_bottom_text_texture: Texture<'a>,
+    pub new_level_generate_text_texture: Texture<'a>,
+    pub generate_level_text_texture: Texture<'a>,
+    pub generate_instructions_text_texture: Texture<'a>,
+    pub generate_seed_text_texture: Texture<'a>,
+    pub generate_rooms_text_texture: Texture<'a>,
+    pub generate_corridor_text_texture: Texture<'a>,
+    pub generate_wall_text_texture: Texture<'a>,
+    pub generate_spotlights_text_texture: Texture<'a>,
+    pub generate_crates_text_texture: Texture<'a>,
 }
 
 impl EditorTextures<'_> {
@@ -86,6 +112,18 @@ impl EditorTextures<'_> {
                 &context.font,
                 "filename:",
             ),
+            save_version_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "version (PAGEUP/DOWN):",
+            ),
+            save_version_losses_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "following will be lost:",
+            ),
             press_y_text_texture: create_text_texture(
                 &mut context.canvas,
                 &context.texture_creator,
@@ -176,6 +214,150 @@ impl EditorTextures<'_> {
                 &context.font,
                 "delete crate",
             ),
+            selection_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "CTRL+C/X: copy/cut, H/V/R: flip/rotate, ESC to clear",
+            ),
+            paste_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "click to paste, H/V/R: flip/rotate, ESC to cancel",
+            ),
+            fill_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "click to fill, D: 4/8 neighbours, F: floors only, ESC to stop",
+            ),
+            line_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag to draw a line, L: next shape, ESC to stop",
+            ),
+            rectangle_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag to draw a rectangle, L: next shape, ESC to stop",
+            ),
+            ellipse_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag to draw an ellipse, L: next shape, ESC to stop",
+            ),
+            brush_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag to paint, [/] or wheel: size, O: square/round, ESC to stop",
+            ),
+            eraser_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag to erase, [/]: size, O: round, D: objects, F: floor tile",
+            ),
+            move_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "drag objects or a box around them, ctrl+arrows: edit, DEL: delete",
+            ),
+            resize_level_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "resize level",
+            ),
+            resize_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "UP/DOWN: select edge, negative crops, ENTER: resize",
+            ),
+            resize_left_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "left:",
+            ),
+            resize_right_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "right:",
+            ),
+            resize_top_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "top:",
+            ),
+            resize_bottom_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "bottom:",
+            ),
+            new_level_generate_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "press G to generate rooms and corridors",
+            ),
+            generate_level_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "generate level",
+            ),
+            generate_instructions_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "UP/DOWN: select, LEFT/RIGHT: toggle, ENTER: generate",
+            ),
+            generate_seed_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "seed:",
+            ),
+            generate_rooms_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "rooms:",
+            ),
+            generate_corridor_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "corridor width:",
+            ),
+            generate_wall_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "wall thickness:",
+            ),
+            generate_spotlights_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "spotlights:",
+            ),
+            generate_crates_text_texture: create_text_texture(
+                &mut context.canvas,
+                &context.texture_creator,
+                &context.font,
+                "crates:",
+            ),
         }
     }
 }
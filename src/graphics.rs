@@ -1,13 +1,17 @@
+/// Zoom levels of the level view, each gives tiles a whole number of pixels.
+pub const ZOOM_LEVELS: [f32; 10] = [0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 2.0, 2.5, 3.0, 4.0];
+
+#[derive(Clone)]
 pub struct Graphics {
     pub tile_size: u32,
-    pub render_multiplier: u32,
+    pub render_multiplier: f32,
     pub resolution_x: u32,
     pub resolution_y: u32,
 }
 
 impl Graphics {
     const TILE_SIZE: u32 = 20;
-    const RENDER_MULTIPLIER: u32 = 2;
+    const RENDER_MULTIPLIER: f32 = 2.0;
     const RESOLUTION_X: u32 = 1280;
     const RESOLUTION_Y: u32 = 720;
 
@@ -21,22 +25,34 @@ impl Graphics {
     }
 
     pub fn get_render_size(&self) -> u32 {
-        self.tile_size * self.render_multiplier
+        (self.tile_size as f32 * self.render_multiplier).round() as u32
     }
 
     pub fn get_x_tiles_per_screen(&self) -> u32 {
         (self.resolution_x + self.get_render_size() - 1) / self.get_render_size()
     }
 
-    pub fn get_full_x_tiles_per_screen(&self) -> u32 {
-        self.resolution_x / self.get_render_size()
-    }
-
     pub fn get_y_tiles_per_screen(&self) -> u32 {
         (self.resolution_y + self.get_render_size() - 1) / self.get_render_size()
     }
 
-    pub fn get_full_y_tiles_per_screen(&self) -> u32 {
-        self.resolution_y / self.get_render_size()
+    /// Moves `steps` zoom levels in or out, stops at the smallest and the
+    /// largest one.
+    pub fn zoom(&mut self, steps: i32) {
+        let current = ZOOM_LEVELS
+            .iter()
+            .position(|&zoom| zoom >= self.render_multiplier)
+            .unwrap_or(ZOOM_LEVELS.len() - 1);
+        let next = (current as i32 + steps).clamp(0, ZOOM_LEVELS.len() as i32 - 1);
+        self.render_multiplier = ZOOM_LEVELS[next as usize];
+    }
+
+    /// Copy at 1x or 2x for the screens showing whole tile sheets, so that
+    /// they stay readable and fit the window at any level zoom.
+    pub fn get_ui_graphics(&self) -> Graphics {
+        Graphics {
+            render_multiplier: self.render_multiplier.round().clamp(1.0, 2.0),
+            ..self.clone()
+        }
     }
 }
@@ -16,21 +16,34 @@ pub fn exec(context: &mut Context) -> NextMode {
         "F1   - this help",
         "F2   - save level",
         "F3   - load level",
-        "F4   - create new level",
+        "F4   - create new level, G to generate one",
+        "F5   - resize level",
         "F6   - enable/disable automatic shadows",
+        "T    - enable/disable automatic wall pieces",
         "F7   - edit general level variables",
         "F8/F9 - edit random crates for normal/dm games",
+        "F10  - save level as png image, F11 - edge scrolling on/off",
         " ",
         "- EDITOR -",
         "Q/W  - place/delete spotlights",
         "A/S  - place/delete steams",
-        "Z/X/C - place/delete crates",
-        "1/2  - place pl1/pl2 start",
-        "SPACE - tile selection/editing mode",
-        "ARROW KEYS - move viewport",
+        "Z/X/C - place/delete crates, 1/2 - place pl1/pl2 start",
+        "SPACE - tile selection/editing mode, SHIFT+CLICK for random tiles",
+        "B    - fill connected tiles",
+        "L    - draw lines, rectangles and ellipses",
+        "P/E  - paint/erase with a brush, [/] to resize",
+        "I    - select objects, SHIFT+CLICK/BOX adds, DRAG or (SHIFT+)ARROWS move",
+        "       CTRL+ARROWS intensity/range/angle/crate, N/D normal/dm crate, DEL delete",
+        "ARROW KEYS/MIDDLE DRAG - move viewport, M - minimap, ALT+G - grid, ALT+L - lighting",
+        "ALT+1-8 - show/hide floors, walls, shadows, spotlights, steams, crates, dm crates, starts",
+        "CTRL+Z/CTRL+Y - undo/redo",
+        "SHIFT+DRAG - select area, CTRL+C/X/V - copy/cut/paste",
+        "H/V  - flip level or selection",
+        "R/SHIFT+R - rotate level or selection",
+        "CTRL+R - pick random tiles of the selection again",
         " ",
         "- WINDOW -",
-        "+/- adjust rendering size",
+        "+/- or MOUSE WHEEL - zoom from 0.25x to 4x",
     ];
     let line_textures: Vec<Texture> = lines
         .iter()
@@ -53,6 +53,7 @@ pub fn exec(context: &mut Context) -> NextMode {
         })
         .collect();
     let mut selected = 0usize;
+    let mut error_text_texture: Option<Texture> = None;
 
     let mut event_pump = context.sdl.event_pump().unwrap();
     loop {
@@ -70,36 +71,48 @@ pub fn exec(context: &mut Context) -> NextMode {
                 }
                 Event::KeyDown { keycode, .. } => match keycode.unwrap() {
                     Keycode::Down => {
-                        if selected < files.len() - 1 {
+                        if selected + 1 < files.len() {
                             selected = selected + 1;
+                            error_text_texture = None;
                         }
                     }
                     Keycode::Up => {
                         if selected > 0 {
                             selected = selected - 1;
+                            error_text_texture = None;
                         }
                     }
                     Keycode::Return | Keycode::KpEnter => {
-                        if files.len() > 0 {
-                            context
-                                .level
-                                .deserialize(&files[selected].filename)
-                                .unwrap();
-                            let level_name = files[selected]
-                                .filename
-                                .strip_prefix("./")
-                                .unwrap()
-                                .to_string();
-                            context.textures.saved_level_name = Some(create_text_texture(
-                                &mut context.canvas,
-                                &context.texture_creator,
-                                &context.font,
-                                &level_name.clone().to_lowercase(),
-                            ));
-                            context.level_save_name =
-                                level_name.strip_suffix(".LEV").unwrap().to_string();
+                        if files.is_empty() {
+                            return Editor;
+                        }
+                        match context.level.deserialize(&files[selected].filename) {
+                            Ok(()) => {
+                                let level_name = files[selected]
+                                    .filename
+                                    .strip_prefix("./")
+                                    .unwrap_or(&files[selected].filename)
+                                    .to_string();
+                                context.textures.saved_level_name = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &level_name.to_lowercase(),
+                                ));
+                                context.level_save_name =
+                                    level_name[..level_name.len() - ".LEV".len()].to_string();
+                                context.history.clear();
+                                return Editor;
+                            }
+                            Err(error) => {
+                                error_text_texture = Some(create_text_texture(
+                                    &mut context.canvas,
+                                    context.texture_creator,
+                                    &context.font,
+                                    &format!("failed to load: {}", error),
+                                ));
+                            }
                         }
-                        return Editor;
                     }
                     _ => {}
                 },
@@ -141,7 +154,9 @@ pub fn exec(context: &mut Context) -> NextMode {
         }
         render::render_text_texture_coordinates(
             &mut context.canvas,
-            &bottom_instruction_text,
+            error_text_texture
+                .as_ref()
+                .unwrap_or(&bottom_instruction_text),
             get_bottom_text_position(context.graphics.resolution_y),
             render_size,
             None,
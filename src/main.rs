@@ -5,17 +5,18 @@ use crate::context_util::get_textures;
 use crate::fn2::create_text_texture;
 use crate::fn2::load_font;
 use crate::graphics::Graphics;
-use crate::level::Level;
 use crate::types::NextMode::*;
 use sdl2::image::InitFlag;
 use sdl2::render::Texture;
+use utk_level::autotile::AutoTileRules;
+use utk_level::brush::Brush;
+use utk_level::history::{History, DEFAULT_DEPTH};
+use utk_level::level::Level;
 mod context;
 mod context_util;
-mod crates;
 mod editor;
 mod general_level_info;
 mod help;
-mod level;
 mod load_level;
 mod random_item_editor;
 mod render;
@@ -63,6 +64,24 @@ pub fn main() {
         level_save_name: String::new(),
         trigonometry: Trigonometry::new(),
         automatic_shadows: true,
+        automatic_walls: false,
+        auto_tile_rules: AutoTileRules::load("./assets/WALLS1_RULES.TXT")
+            .expect("unable to load wall rules"),
+        history: History::new(get_undo_depth()),
+        clipboard: None,
+        variation_brush: None,
+        brush: Brush::default(),
+        eraser_tile: Tile {
+            texture_type: TextureType::FLOOR,
+            id: 0,
+            shadow: 0,
+        },
+        eraser_removes_objects: false,
+        show_minimap: false,
+        edge_scrolling: true,
+        layers: Layers::default(),
+        show_grid: false,
+        show_lighting: false,
     };
 
     let mut next_mode = NextMode::Editor;
@@ -72,10 +91,39 @@ pub fn main() {
             Editor => editor::exec(&mut context),
             TileSelect => tile_selector::exec(&mut context),
             Help => help::exec(&mut context),
-            GeneralLevelInfo => general_level_info::exec(&mut context),
-            RandomItemEditor(game_type) => random_item_editor::exec(&mut context, game_type),
+            GeneralLevelInfo => {
+                context.history.begin(&context.level);
+                let next_mode = general_level_info::exec(&mut context);
+                context.history.commit(&context.level);
+                next_mode
+            }
+            RandomItemEditor(game_type) => {
+                context.history.begin(&context.level);
+                let next_mode = random_item_editor::exec(&mut context, game_type);
+                context.history.commit(&context.level);
+                next_mode
+            }
             LoadLevel => load_level::exec(&mut context),
             Quit => break 'running,
         }
     }
 }
+
+// Number of undo steps, set with --undo-depth <N>. A missing or invalid
+// number falls back to the default.
+fn get_undo_depth() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(index) = args.iter().position(|arg| arg == "--undo-depth") else {
+        return DEFAULT_DEPTH;
+    };
+    match args.get(index + 1).and_then(|depth| depth.parse().ok()) {
+        Some(depth) => depth,
+        None => {
+            eprintln!(
+                "warning: --undo-depth needs a number, using {}",
+                DEFAULT_DEPTH
+            );
+            DEFAULT_DEPTH
+        }
+    }
+}
@@ -2,7 +2,6 @@ extern crate sdl2;
 
 use crate::context_util::resize;
 use crate::create_text_texture;
-use crate::level::Level;
 use crate::render;
 use crate::types::*;
 use crate::util::{get_bottom_text_position, TITLE_POSITION};
@@ -12,6 +11,7 @@ use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
 use sdl2::render::Texture;
+use utk_level::level::Level;
 
 fn load_text<'a>(context: &mut Context<'a>, text: &str) -> Texture<'a> {
     create_text_texture(
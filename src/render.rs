@@ -1,23 +1,37 @@
-use crate::crates::CrateClass;
-use crate::level::DIFF_BULLETS;
-use crate::level::DIFF_WEAPONS;
-use crate::level::{StaticCrate, StaticCrateType};
 use crate::types::*;
 use crate::util::*;
 use crate::Graphics;
 use crate::Level;
 use crate::Textures;
 use sdl2::pixels::Color;
+use sdl2::pixels::PixelFormatEnum;
 use sdl2::rect::Point;
 use sdl2::rect::Rect;
+use sdl2::render::BlendMode;
 use sdl2::render::Canvas;
 use sdl2::render::Texture;
+use sdl2::render::TextureCreator;
 use sdl2::render::TextureQuery;
 use sdl2::video::Window;
-use std::collections::HashMap;
+use sdl2::video::WindowContext;
+use std::cmp;
+use std::collections::BTreeMap;
 use std::time::Duration;
+use utk_level::area::{Area, Clipboard};
+use utk_level::crates::CrateClass;
+use utk_level::level::DIFF_BULLETS;
+use utk_level::level::DIFF_WEAPONS;
+use utk_level::level::TILE_SIZE;
+use utk_level::level::{StaticCrate, StaticCrateType, Steam};
+use utk_level::objects::{ObjectKind, ObjectRef};
 
 pub const TEXT_SIZE_MULTIPLIER: u32 = 2;
+const PREVIEW_ALPHA: u8 = 128;
+const MINIMAP_OBJECT_SIZE: u32 = 3;
+const MINIMAP_PLAYER_SIZE: u32 = 5;
+// Level pixels per light texture pixel, at least
+const LIGHT_SAMPLE_STEP: u32 = 2;
+const LIGHT_TEXTURE_MAX_SIZE: u32 = 2048;
 
 pub enum RendererColor {
     White,
@@ -25,6 +39,7 @@ pub enum RendererColor {
     Blue,
     LightBlue,
     LightGreen,
+    Gray,
 }
 
 fn get_sdl_color(color: &RendererColor) -> Color {
@@ -34,6 +49,7 @@ fn get_sdl_color(color: &RendererColor) -> Color {
         RendererColor::Blue => Color::from((0, 0, 255)),
         RendererColor::LightBlue => Color::from((100, 100, 255)),
         RendererColor::LightGreen => Color::from((100, 255, 100)),
+        RendererColor::Gray => Color::from((90, 90, 90)),
     }
 }
 
@@ -47,14 +63,11 @@ pub fn highlight_selected_tile(
     canvas.set_draw_color(sdl_color);
 
     let render_size = graphics.get_render_size();
-    let render_multiplier = graphics.render_multiplier;
-    let (x_logical, y_logical) = get_tile_coordinates(
+    let (x, y) = get_tile_coordinates(
         id,
-        graphics.get_x_tiles_per_screen() * graphics.tile_size,
-        graphics.tile_size,
+        graphics.get_x_tiles_per_screen() * render_size,
+        render_size,
     );
-    let x = x_logical * render_multiplier;
-    let y = y_logical * render_multiplier;
 
     draw_line(canvas, x, y, x, y + render_size - 1);
     draw_line(canvas, x, y, x + render_size - 1, y);
@@ -188,50 +201,120 @@ pub fn render_level(
     level: &Level,
     textures: &Textures,
     trigonometry: &Trigonometry,
+    layers: &Layers,
+    light_texture: Option<&Texture>,
 ) {
     canvas.set_draw_color(Color::from((0, 0, 0)));
     canvas.clear();
     let render_size = graphics.get_render_size();
+    let first_tile = (
+        level.scroll.0 / graphics.tile_size,
+        level.scroll.1 / graphics.tile_size,
+    );
 
-    for y in 0..std::cmp::min(level.tiles.len() as u32, graphics.get_y_tiles_per_screen()) {
-        for x in 0..std::cmp::min(
-            level.tiles[y as usize].len() as u32,
-            graphics.get_x_tiles_per_screen(),
-        ) {
-            let (x_index, y_index) = get_scroll_corrected_indexes(level.scroll, x, y);
-            if y_index >= level.tiles.len() || x_index >= level.tiles[y_index].len() {
+    // One more tile than fits the screen, as the first one may be cut
+    for y in 0..=graphics.get_y_tiles_per_screen() {
+        for x in 0..=graphics.get_x_tiles_per_screen() {
+            let (x_index, y_index) = (first_tile.0 + x, first_tile.1 + y);
+            if y_index as usize >= level.tiles.len()
+                || x_index as usize >= level.tiles[y_index as usize].len()
+            {
                 continue;
             }
-            let texture = match level.tiles[y_index][x_index].texture_type {
-                TextureType::FLOOR => &textures.floor,
-                TextureType::WALLS => &textures.walls,
-                TextureType::SHADOW => unreachable!(),
-            };
-            let (texture_width, _texture_height) = get_texture_size(texture);
-            let src = get_block(
-                level.tiles[y_index][x_index].id,
-                texture_width,
-                graphics.tile_size,
+            let (x_screen, y_screen) = get_screen_coordinates_from_level_coordinates(
+                graphics,
+                &(x_index * graphics.tile_size, y_index * graphics.tile_size),
+                &level.scroll,
+            );
+            let dst = Rect::new(x_screen, y_screen, render_size, render_size);
+            render_tile(
+                canvas,
+                graphics,
+                textures,
+                layers,
+                &level.tiles[y_index as usize][x_index as usize],
+                dst,
             );
-            let (x_absolute, y_absolute) =
-                get_absolute_coordinates_from_logical(x, y, graphics.get_render_size());
-            let dst = Rect::new(x_absolute, y_absolute, render_size, render_size);
-            canvas.copy(texture, src, dst).unwrap();
-            let (shadow_texture_width, _shadow_texture_height) =
-                get_texture_size(&textures.shadows);
-            if level.tiles[y_index][x_index].shadow > 0 {
-                let src = get_block(
-                    level.tiles[y_index][x_index].shadow - 1,
-                    shadow_texture_width,
-                    graphics.tile_size,
-                );
-                canvas.copy(&textures.shadows, src, dst).unwrap();
-            }
         }
     }
-    for (coordinates, spotlight) in &level.spotlights {
+    if let Some(texture) = light_texture {
+        let level_pixels = get_level_pixel_size(
+            graphics,
+            (level.tiles[0].len() as u32, level.tiles.len() as u32),
+        );
         let (x_screen, y_screen) =
-            get_screen_coordinates_from_level_coordinates(graphics, coordinates, &level.scroll);
+            get_screen_coordinates_from_level_coordinates(graphics, &(0, 0), &level.scroll);
+        let render_size = graphics.get_render_size();
+        let dst = Rect::new(
+            x_screen,
+            y_screen,
+            level_pixels.0 * render_size / graphics.tile_size,
+            level_pixels.1 * render_size / graphics.tile_size,
+        );
+        canvas.copy(texture, None, dst).unwrap();
+    }
+    render_objects(
+        canvas,
+        graphics,
+        &level.scroll,
+        textures,
+        trigonometry,
+        layers,
+        (0, 0),
+        &level.spotlights,
+        &level.steams,
+        &level.crates.staticc,
+    );
+}
+
+fn render_tile(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    textures: &Textures,
+    layers: &Layers,
+    tile: &Tile,
+    dst: Rect,
+) {
+    let (texture, visible) = match tile.texture_type {
+        TextureType::FLOOR => (&textures.floor, layers.floors),
+        TextureType::WALLS => (&textures.walls, layers.walls),
+        TextureType::SHADOW => unreachable!(),
+    };
+    if visible {
+        let (texture_width, _texture_height) = get_texture_size(texture);
+        let src = get_block(tile.id, texture_width, graphics.tile_size);
+        canvas.copy(texture, src, dst).unwrap();
+    }
+    let (shadow_texture_width, _shadow_texture_height) = get_texture_size(&textures.shadows);
+    if tile.shadow > 0 && layers.shadows {
+        let src = get_block(tile.shadow - 1, shadow_texture_width, graphics.tile_size);
+        canvas.copy(&textures.shadows, src, dst).unwrap();
+    }
+}
+
+// Objects are drawn at their level coordinates moved by offset
+#[allow(clippy::too_many_arguments)]
+fn render_objects(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    scroll: &(u32, u32),
+    textures: &Textures,
+    trigonometry: &Trigonometry,
+    layers: &Layers,
+    offset: (u32, u32),
+    spotlights: &BTreeMap<(u32, u32), u8>,
+    steams: &BTreeMap<(u32, u32), Steam>,
+    crates: &BTreeMap<(u32, u32), StaticCrateType>,
+) {
+    let get_screen_coordinates = |coordinates: &(u32, u32)| {
+        get_screen_coordinates_from_level_coordinates(
+            graphics,
+            &(coordinates.0 + offset.0, coordinates.1 + offset.1),
+            scroll,
+        )
+    };
+    for (coordinates, spotlight) in spotlights.iter().filter(|_| layers.spotlights) {
+        let (x_screen, y_screen) = get_screen_coordinates(coordinates);
         draw_circle(
             canvas,
             x_screen,
@@ -240,9 +323,8 @@ pub fn render_level(
             &RendererColor::Blue,
         );
     }
-    for (coordinates, steam) in &level.steams {
-        let (x_screen, y_screen) =
-            get_screen_coordinates_from_level_coordinates(graphics, coordinates, &level.scroll);
+    for (coordinates, steam) in steams.iter().filter(|_| layers.steams) {
+        let (x_screen, y_screen) = get_screen_coordinates(coordinates);
         for x in 0..6 {
             let multiplier = x as f32 * 6.0 * steam.range as f32;
             draw_circle(
@@ -255,13 +337,359 @@ pub fn render_level(
         }
     }
 
-    render_crates(
+    let crates = crates
+        .iter()
+        .filter(|(_, crate_item)| layers.shows_crate(&crate_item.crate_variant))
+        .map(|(coordinates, crate_item)| {
+            (
+                (coordinates.0 + offset.0, coordinates.1 + offset.1),
+                *crate_item,
+            )
+        })
+        .collect();
+    render_crates(canvas, graphics, scroll, textures, &crates);
+}
+
+/// Translucent clipboard content with its top left corner on `position` tile.
+#[allow(clippy::too_many_arguments)]
+pub fn render_clipboard_preview(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    scroll: &(u32, u32),
+    textures: &mut Textures,
+    trigonometry: &Trigonometry,
+    layers: &Layers,
+    clipboard: &Clipboard,
+    position: &(u32, u32),
+) {
+    let render_size = graphics.get_render_size();
+    for texture in [
+        &mut textures.floor,
+        &mut textures.walls,
+        &mut textures.shadows,
+    ] {
+        texture.set_blend_mode(BlendMode::Blend);
+        texture.set_alpha_mod(PREVIEW_ALPHA);
+    }
+    for (y, row) in clipboard.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            let (x_screen, y_screen) = get_screen_coordinates_from_level_coordinates(
+                graphics,
+                &(
+                    (position.0 + x as u32) * graphics.tile_size,
+                    (position.1 + y as u32) * graphics.tile_size,
+                ),
+                scroll,
+            );
+            let dst = Rect::new(x_screen, y_screen, render_size, render_size);
+            render_tile(canvas, graphics, textures, layers, tile, dst);
+        }
+    }
+    for texture in [
+        &mut textures.floor,
+        &mut textures.walls,
+        &mut textures.shadows,
+    ] {
+        texture.set_alpha_mod(255);
+    }
+    textures.floor.set_blend_mode(BlendMode::None);
+    textures.walls.set_blend_mode(BlendMode::None);
+    render_objects(
         canvas,
         graphics,
+        scroll,
+        textures,
+        trigonometry,
+        layers,
+        (
+            position.0 * graphics.tile_size,
+            position.1 * graphics.tile_size,
+        ),
+        &clipboard.spotlights,
+        &clipboard.steams,
+        &clipboard.crates,
+    );
+    let (width, height) = clipboard.get_size();
+    highlight_level_area(
+        canvas,
+        graphics,
+        scroll,
+        &Area {
+            x: position.0,
+            y: position.1,
+            width,
+            height,
+        },
+        &RendererColor::White,
+    );
+}
+
+pub fn highlight_level_area(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    scroll: &(u32, u32),
+    area: &Area,
+    color: &RendererColor,
+) {
+    let render_size = graphics.get_render_size();
+    let (x_screen, y_screen) = get_screen_coordinates_from_level_coordinates(
+        graphics,
+        &(area.x * graphics.tile_size, area.y * graphics.tile_size),
+        scroll,
+    );
+    canvas.set_draw_color(get_sdl_color(color));
+    canvas
+        .draw_rect(Rect::new(
+            x_screen,
+            y_screen,
+            area.width * render_size,
+            area.height * render_size,
+        ))
+        .unwrap();
+}
+
+/// Outlines each of the level `tiles`, like a shape being drawn.
+pub fn highlight_level_tiles(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    scroll: &(u32, u32),
+    tiles: &[(u32, u32)],
+    color: &RendererColor,
+) {
+    for tile in tiles {
+        highlight_level_area(
+            canvas,
+            graphics,
+            scroll,
+            &Area::from_corners(tile, tile),
+            color,
+        );
+    }
+}
+
+/// Rectangle between two level pixel corners, like a box being dragged.
+pub fn highlight_level_box(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    scroll: &(u32, u32),
+    corner: &(u32, u32),
+    other_corner: &(u32, u32),
+    color: &RendererColor,
+) {
+    let first = get_screen_coordinates_from_level_coordinates(
+        graphics,
+        &(corner.0.min(other_corner.0), corner.1.min(other_corner.1)),
+        scroll,
+    );
+    let last = get_screen_coordinates_from_level_coordinates(
+        graphics,
+        &(corner.0.max(other_corner.0), corner.1.max(other_corner.1)),
+        scroll,
+    );
+    canvas.set_draw_color(get_sdl_color(color));
+    canvas
+        .draw_rect(Rect::new(
+            first.0,
+            first.1,
+            (last.0 - first.0 + 1) as u32,
+            (last.1 - first.1 + 1) as u32,
+        ))
+        .unwrap();
+}
+
+/// Square around a spotlight, steam or static crate of the level, nothing
+/// if the object is gone.
+pub fn highlight_object(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    level: &Level,
+    object: &ObjectRef,
+    color: &RendererColor,
+) {
+    let (x_screen, y_screen) =
+        get_screen_coordinates_from_level_coordinates(graphics, &object.position, &level.scroll);
+    let margin = 3;
+    // Distance of the top left corner from the object position and size
+    let square = match object.kind {
+        ObjectKind::Spotlight => level
+            .spotlights
+            .get(&object.position)
+            .map(|spotlight| get_spotlight_render_radius(spotlight) + margin)
+            .map(|radius| (radius as i32, radius * 2)),
+        ObjectKind::Steam => level
+            .steams
+            .get(&object.position)
+            .map(|_| get_steam_render_radius() + margin)
+            .map(|radius| (radius as i32, radius * 2)),
+        ObjectKind::Crate => level
+            .crates
+            .staticc
+            .get(&object.position)
+            .map(|_| (margin as i32, get_crate_render_size() + margin * 2)),
+    };
+    if let Some((offset, size)) = square {
+        canvas.set_draw_color(get_sdl_color(color));
+        canvas
+            .draw_rect(Rect::new(x_screen - offset, y_screen - offset, size, size))
+            .unwrap();
+    }
+}
+
+/// Lines between the level tiles on screen.
+pub fn render_grid(canvas: &mut Canvas<Window>, graphics: &Graphics, level: &Level) {
+    let tile_size = graphics.tile_size;
+    let level_size = (level.tiles[0].len() as u32, level.tiles.len() as u32);
+    let first_tile = (level.scroll.0 / tile_size, level.scroll.1 / tile_size);
+    let (level_end_x, level_end_y) = get_screen_coordinates_from_level_coordinates(
+        graphics,
+        &get_level_pixel_size(graphics, level_size),
         &level.scroll,
-        &textures,
-        &level.crates.staticc,
     );
+    canvas.set_draw_color(get_sdl_color(&RendererColor::Gray));
+    for x in first_tile.0..level_size.0 {
+        let (x_screen, _) = get_screen_coordinates_from_level_coordinates(
+            graphics,
+            &(x * tile_size, 0),
+            &level.scroll,
+        );
+        if x_screen >= graphics.resolution_x as i32 {
+            break;
+        }
+        canvas
+            .draw_line(
+                Point::new(x_screen, 0),
+                Point::new(x_screen, level_end_y - 1),
+            )
+            .unwrap();
+    }
+    for y in first_tile.1..level_size.1 {
+        let (_, y_screen) = get_screen_coordinates_from_level_coordinates(
+            graphics,
+            &(0, y * tile_size),
+            &level.scroll,
+        );
+        if y_screen >= graphics.resolution_y as i32 {
+            break;
+        }
+        canvas
+            .draw_line(
+                Point::new(0, y_screen),
+                Point::new(level_end_x - 1, y_screen),
+            )
+            .unwrap();
+    }
+}
+
+/// Black overlay for the whole level that is more transparent where the
+/// spotlights light it, stretched over the level tiles.
+pub fn create_light_texture<'a>(
+    texture_creator: &'a TextureCreator<WindowContext>,
+    level: &Level,
+) -> Texture<'a> {
+    let x_tiles = level.tiles[0].len() as u32;
+    let largest = cmp::max(x_tiles, level.tiles.len() as u32) * TILE_SIZE;
+    // Big levels are sampled more sparsely to keep the texture small
+    let step = cmp::max(LIGHT_SAMPLE_STEP, largest.div_ceil(LIGHT_TEXTURE_MAX_SIZE));
+    let light_map = level.get_light_map(step);
+    let pixels: Vec<u8> = light_map
+        .values
+        .iter()
+        .flat_map(|light| [0, 0, 0, ((1.0 - light) * 255.0).round() as u8])
+        .collect();
+    let mut texture = texture_creator
+        .create_texture_static(PixelFormatEnum::RGBA32, light_map.width, light_map.height)
+        .unwrap();
+    texture
+        .update(None, &pixels, light_map.width as usize * 4)
+        .unwrap();
+    texture.set_blend_mode(BlendMode::Blend);
+    texture
+}
+
+/// One pixel per tile of the level, stretched into the minimap.
+pub fn create_minimap_texture<'a>(
+    texture_creator: &'a TextureCreator<WindowContext>,
+    level: &Level,
+) -> Texture<'a> {
+    let image = utk_level::render::render_minimap(level);
+    let mut texture = texture_creator
+        .create_texture_static(PixelFormatEnum::RGBA32, image.width, image.height)
+        .unwrap();
+    texture
+        .update(None, &image.pixels, image.width as usize * 4)
+        .unwrap();
+    texture
+}
+
+/// Level tiles from `create_minimap_texture` shrunk into `rect` with dots
+/// for objects and player starts and an outline around the part of the
+/// level on screen.
+pub fn render_minimap(
+    canvas: &mut Canvas<Window>,
+    graphics: &Graphics,
+    level: &Level,
+    texture: &Texture,
+    rect: Rect,
+) {
+    canvas.copy(texture, None, rect).unwrap();
+
+    let TextureQuery { width, height, .. } = texture.query();
+    let level_pixels = get_level_pixel_size(graphics, (width, height));
+    let to_minimap = |coordinates: &(u32, u32)| {
+        (
+            rect.x() + (coordinates.0 as u64 * rect.width() as u64 / level_pixels.0 as u64) as i32,
+            rect.y() + (coordinates.1 as u64 * rect.height() as u64 / level_pixels.1 as u64) as i32,
+        )
+    };
+    let mut draw_dot = |coordinates: &(u32, u32), size: u32, color: &RendererColor| {
+        let (x, y) = to_minimap(coordinates);
+        canvas.set_draw_color(get_sdl_color(color));
+        canvas
+            .fill_rect(Rect::new(
+                x - size as i32 / 2,
+                y - size as i32 / 2,
+                size,
+                size,
+            ))
+            .unwrap();
+    };
+    for coordinates in level.spotlights.keys() {
+        draw_dot(coordinates, MINIMAP_OBJECT_SIZE, &RendererColor::Blue);
+    }
+    for coordinates in level.steams.keys() {
+        draw_dot(coordinates, MINIMAP_OBJECT_SIZE, &RendererColor::Red);
+    }
+    for (coordinates, crate_item) in &level.crates.staticc {
+        let color = match crate_item.crate_variant {
+            StaticCrate::Normal => RendererColor::LightGreen,
+            StaticCrate::Deathmatch => RendererColor::LightBlue,
+        };
+        draw_dot(coordinates, MINIMAP_OBJECT_SIZE, &color);
+    }
+    for position in [level.p1_position, level.p2_position] {
+        let center = (
+            position.0 * graphics.tile_size + graphics.tile_size / 2,
+            position.1 * graphics.tile_size + graphics.tile_size / 2,
+        );
+        draw_dot(&center, MINIMAP_PLAYER_SIZE, &RendererColor::White);
+    }
+
+    let viewport = get_viewport_pixel_size(graphics);
+    let first = to_minimap(&level.scroll);
+    let last = to_minimap(&(
+        (level.scroll.0 + viewport.0).min(level_pixels.0),
+        (level.scroll.1 + viewport.1).min(level_pixels.1),
+    ));
+    canvas.set_draw_color(get_sdl_color(&RendererColor::White));
+    canvas
+        .draw_rect(Rect::new(
+            first.0,
+            first.1,
+            (last.0 - first.0).max(1) as u32,
+            (last.1 - first.1).max(1) as u32,
+        ))
+        .unwrap();
+    canvas.draw_rect(rect).unwrap();
 }
 
 fn render_crates(
@@ -269,7 +697,7 @@ fn render_crates(
     graphics: &Graphics,
     scroll: &(u32, u32),
     textures: &Textures,
-    crates: &HashMap<(u32, u32), StaticCrateType>,
+    crates: &BTreeMap<(u32, u32), StaticCrateType>,
 ) {
     for (coordinates, crate_item) in crates {
         let box_size = get_crate_render_size();
@@ -314,7 +742,7 @@ pub fn render_and_wait(canvas: &mut Canvas<Window>) {
     ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
 }
 
-pub fn get_texture_rect(texture: &Texture, render_multiplier: u32) -> Rect {
+pub fn get_texture_rect(texture: &Texture, render_multiplier: f32) -> Rect {
     let (width, height) = get_texture_render_size(&texture, render_multiplier);
     Rect::new(0, 0, width, height)
 }
@@ -324,7 +752,10 @@ pub fn get_texture_size(texture: &Texture) -> (u32, u32) {
     (width, height)
 }
 
-pub fn get_texture_render_size(texture: &Texture, render_multiplier: u32) -> (u32, u32) {
+pub fn get_texture_render_size(texture: &Texture, render_multiplier: f32) -> (u32, u32) {
     let (width, height) = get_texture_size(&texture);
-    (width * render_multiplier, height * render_multiplier)
+    (
+        (width as f32 * render_multiplier).round() as u32,
+        (height as f32 * render_multiplier).round() as u32,
+    )
 }
@@ -6,11 +6,15 @@ use crate::render;
 use crate::types::*;
 use crate::util::*;
 use crate::Context;
+use crate::Graphics;
 use crate::NextMode::*;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::keyboard::Mod;
 use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
+use sdl2::render::Texture;
+use utk_level::variation::VariationBrush;
 
 pub fn exec(context: &mut Context) -> NextMode {
     let floor_blocks_text_texture = create_text_texture(
@@ -31,6 +35,14 @@ pub fn exec(context: &mut Context) -> NextMode {
         &context.font,
         "shadows (PAGEGUP/DOWN) - clear with RIGHT CLICK",
     );
+    let add_to_brush_text_texture = create_text_texture(
+        &mut context.canvas,
+        &context.texture_creator,
+        &context.font,
+        "SHIFT+CLICK to paint random tiles, again for more weight",
+    );
+    let mut brush_textures = create_brush_textures(context);
+    let graphics = context.graphics.get_ui_graphics();
     let mut event_pump = context.sdl.event_pump().unwrap();
     loop {
         for event in event_pump.poll_iter() {
@@ -69,6 +81,20 @@ pub fn exec(context: &mut Context) -> NextMode {
                                 TextureType::FLOOR
                             }
                     }
+                    key @ (Keycode::Up | Keycode::Down) => {
+                        if let Some(brush) = &mut context.variation_brush {
+                            brush.seed = if key == Keycode::Up {
+                                brush.seed.saturating_add(1)
+                            } else {
+                                brush.seed.saturating_sub(1)
+                            };
+                            brush_textures = create_brush_textures(context);
+                        }
+                    }
+                    Keycode::Backspace => {
+                        context.variation_brush = None;
+                        brush_textures = None;
+                    }
                     _ => {}
                 },
                 Event::MouseMotion { x, y, .. } => {
@@ -86,23 +112,42 @@ pub fn exec(context: &mut Context) -> NextMode {
                     };
                     let (texture_width, texture_height) = render::get_texture_render_size(
                         texture_selected,
-                        context.graphics.render_multiplier,
+                        graphics.render_multiplier,
                     );
                     let clicked_tile_id = get_tile_id_from_coordinates(
-                        &context.graphics,
+                        &graphics,
                         &limit_coordinates(&context.mouse, &(texture_width, texture_height)),
-                        texture_width / context.graphics.get_render_size(),
+                        texture_width / graphics.get_render_size(),
                         None,
                     );
                     if clicked_tile_id
-                        < get_number_of_tiles_in_texture(
-                            texture_selected,
-                            context.graphics.tile_size,
-                        )
+                        < get_number_of_tiles_in_texture(texture_selected, graphics.tile_size)
                     {
                         context.selected_tile_id = clicked_tile_id;
                         context.texture_type_selected = context.texture_type_scrolled;
-                        return Editor;
+                        let shift_held = context
+                            .sdl
+                            .keyboard()
+                            .mod_state()
+                            .intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+                        if !shift_held {
+                            context.variation_brush = None;
+                            return Editor;
+                        }
+                        // Tiles of one texture only, picking from another
+                        // starts a new brush
+                        let mut brush = match context.variation_brush.take() {
+                            Some(brush) if brush.texture_type == context.texture_type_scrolled => {
+                                brush
+                            }
+                            Some(brush) => {
+                                VariationBrush::new(context.texture_type_scrolled, brush.seed)
+                            }
+                            None => VariationBrush::new(context.texture_type_scrolled, 0),
+                        };
+                        brush.add(clicked_tile_id);
+                        context.variation_brush = Some(brush);
+                        brush_textures = create_brush_textures(context);
                     }
                 }
                 _ => {}
@@ -116,7 +161,7 @@ pub fn exec(context: &mut Context) -> NextMode {
             TextureType::WALLS => &context.textures.walls,
             TextureType::SHADOW => &context.textures.shadows,
         };
-        let render_multiplier = context.graphics.render_multiplier;
+        let render_multiplier = graphics.render_multiplier;
         let dst = render::get_texture_rect(texture_selected, render_multiplier);
         context.canvas.set_draw_color(Color::from((200, 200, 200)));
         context.canvas.fill_rect(dst).unwrap();
@@ -124,40 +169,81 @@ pub fn exec(context: &mut Context) -> NextMode {
         let (texture_width, texture_height) =
             render::get_texture_render_size(texture_selected, render_multiplier);
         let highlighted_id = get_tile_id_from_coordinates(
-            &context.graphics,
+            &graphics,
             &limit_coordinates(&context.mouse, &(texture_width, texture_height)),
-            context.graphics.get_x_tiles_per_screen(),
+            graphics.get_x_tiles_per_screen(),
             None,
         );
         render::highlight_selected_tile(
             &mut context.canvas,
-            &context.graphics,
+            &graphics,
             highlighted_id,
             &render::RendererColor::White,
         );
-        if context.texture_type_selected == context.texture_type_scrolled {
-            let coordinates = get_tile_coordinates(
-                context.selected_tile_id,
-                texture_width / context.graphics.render_multiplier,
-                context.graphics.tile_size,
-            );
-            let render_multiplier = context.graphics.render_multiplier;
+        let brush = context
+            .variation_brush
+            .as_ref()
+            .filter(|brush| brush.texture_type == context.texture_type_scrolled);
+        if let (Some(brush), Some((weight_textures, _))) = (brush, &brush_textures) {
+            for (&(id, _), weight_text_texture) in brush.get_weights().iter().zip(weight_textures) {
+                let coordinates = get_tile_screen_coordinates(&graphics, id, texture_width);
+                let screen_tile_id = get_tile_id_from_coordinates(
+                    &graphics,
+                    &coordinates,
+                    graphics.get_x_tiles_per_screen(),
+                    None,
+                );
+                render::highlight_selected_tile(
+                    &mut context.canvas,
+                    &graphics,
+                    screen_tile_id,
+                    &render::RendererColor::LightGreen,
+                );
+                render::render_text_texture_coordinates(
+                    &mut context.canvas,
+                    weight_text_texture,
+                    (coordinates.0 + 4, coordinates.1 + 4),
+                    graphics.get_render_size(),
+                    None,
+                );
+            }
+        } else if context.texture_type_selected == context.texture_type_scrolled {
+            let coordinates =
+                get_tile_screen_coordinates(&graphics, context.selected_tile_id, texture_width);
             let screen_tile_id = get_tile_id_from_coordinates(
-                &context.graphics,
-                &(
-                    coordinates.0 * render_multiplier,
-                    coordinates.1 * render_multiplier,
-                ),
-                context.graphics.get_x_tiles_per_screen(),
+                &graphics,
+                &coordinates,
+                graphics.get_x_tiles_per_screen(),
                 None,
             );
             render::highlight_selected_tile(
                 &mut context.canvas,
-                &context.graphics,
+                &graphics,
                 screen_tile_id,
                 &render::RendererColor::Red,
             );
         }
+        let (x, y) = get_bottom_text_position(graphics.resolution_y);
+        match &brush_textures {
+            Some((_, brush_text_texture)) => {
+                render::render_text_texture(
+                    &mut context.canvas,
+                    brush_text_texture,
+                    x,
+                    y - 30,
+                    graphics.get_render_size(),
+                    None,
+                );
+            }
+            None => render::render_text_texture(
+                &mut context.canvas,
+                &add_to_brush_text_texture,
+                x,
+                y - 30,
+                graphics.get_render_size(),
+                None,
+            ),
+        }
         let active_text = match context.texture_type_scrolled {
             TextureType::FLOOR => &floor_blocks_text_texture,
             TextureType::WALLS => &wall_blocks_text_texture,
@@ -166,10 +252,43 @@ pub fn exec(context: &mut Context) -> NextMode {
         render::render_text_texture_coordinates(
             &mut context.canvas,
             active_text,
-            get_bottom_text_position(context.graphics.resolution_y),
-            context.graphics.get_render_size(),
+            get_bottom_text_position(graphics.resolution_y),
+            graphics.get_render_size(),
             None,
         );
         render::render_and_wait(&mut context.canvas);
     }
 }
+
+// Top left corner of a tile of the texture on screen
+fn get_tile_screen_coordinates(graphics: &Graphics, id: u32, texture_width: u32) -> (u32, u32) {
+    get_tile_coordinates(id, texture_width, graphics.get_render_size())
+}
+
+// Weight labels of the brush tiles, in the order of its weights, and the
+// brush status line
+fn create_brush_textures<'a>(context: &mut Context<'a>) -> Option<(Vec<Texture<'a>>, Texture<'a>)> {
+    let brush = context.variation_brush.as_ref()?;
+    let weight_textures = brush
+        .get_weights()
+        .iter()
+        .map(|(_, weight)| {
+            create_text_texture(
+                &mut context.canvas,
+                context.texture_creator,
+                &context.font,
+                &weight.to_string(),
+            )
+        })
+        .collect();
+    let brush_text_texture = create_text_texture(
+        &mut context.canvas,
+        context.texture_creator,
+        &context.font,
+        &format!(
+            "random brush seed {} (UP/DOWN), BACKSPACE to clear",
+            brush.seed
+        ),
+    );
+    Some((weight_textures, brush_text_texture))
+}
@@ -1,29 +1,6 @@
-#[derive(Clone, Copy, PartialEq)]
-pub enum TextureType {
-    FLOOR = 0,
-    WALLS = 1,
-    SHADOW = 2,
-}
-
-impl TextureType {
-    pub fn from_u32(value: u32) -> TextureType {
-        match value {
-            0 => TextureType::FLOOR,
-            1 => TextureType::WALLS,
-            2 => TextureType::SHADOW,
-            _ => panic!("Unknown value: {}", value),
-        }
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct Tile {
-    pub(crate) texture_type: TextureType,
-    pub(crate) id: u32,
-    pub(crate) shadow: u32,
-}
-
-pub type Tiles = Vec<Vec<Tile>>;
+use utk_level::level::{Level, StaticCrate};
+use utk_level::objects::{ObjectKind, ObjectRef};
+pub use utk_level::types::*;
 
 pub enum GameType {
     Normal,
@@ -44,3 +21,82 @@ pub struct Trigonometry {
     pub(crate) sin: [f32; 360],
     pub(crate) cos: [f32; 360],
 }
+
+/// Parts of the level drawn in the editor, hiding some shows what is under
+/// the others on dense levels.
+#[derive(Clone, Copy)]
+pub struct Layers {
+    pub floors: bool,
+    pub walls: bool,
+    pub shadows: bool,
+    pub spotlights: bool,
+    pub steams: bool,
+    pub normal_crates: bool,
+    pub deathmatch_crates: bool,
+    pub player_starts: bool,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Layers {
+            floors: true,
+            walls: true,
+            shadows: true,
+            spotlights: true,
+            steams: true,
+            normal_crates: true,
+            deathmatch_crates: true,
+            player_starts: true,
+        }
+    }
+}
+
+impl Layers {
+    pub const NAMES: [&'static str; 8] = [
+        "floors",
+        "walls",
+        "shadows",
+        "spotlights",
+        "steams",
+        "normal crates",
+        "dm crates",
+        "player starts",
+    ];
+
+    /// Shows or hides the layer named at `index` of `NAMES`, returns true if
+    /// it is visible afterwards.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        let layer = match index {
+            0 => &mut self.floors,
+            1 => &mut self.walls,
+            2 => &mut self.shadows,
+            3 => &mut self.spotlights,
+            4 => &mut self.steams,
+            5 => &mut self.normal_crates,
+            6 => &mut self.deathmatch_crates,
+            7 => &mut self.player_starts,
+            _ => panic!("no layer {}", index),
+        };
+        *layer = !*layer;
+        *layer
+    }
+
+    pub fn shows_crate(&self, crate_variant: &StaticCrate) -> bool {
+        match crate_variant {
+            StaticCrate::Normal => self.normal_crates,
+            StaticCrate::Deathmatch => self.deathmatch_crates,
+        }
+    }
+
+    pub fn shows_object(&self, level: &Level, object: &ObjectRef) -> bool {
+        match object.kind {
+            ObjectKind::Spotlight => self.spotlights,
+            ObjectKind::Steam => self.steams,
+            ObjectKind::Crate => level
+                .crates
+                .staticc
+                .get(&object.position)
+                .is_some_and(|crate_item| self.shows_crate(&crate_item.crate_variant)),
+        }
+    }
+}
@@ -2,38 +2,35 @@ use crate::types::Trigonometry;
 use crate::Graphics;
 use sdl2::rect::Rect;
 use sdl2::render::{Texture, TextureQuery};
-use std::cmp;
+pub use utk_level::util::*;
 
 pub const TITLE_POSITION: (u32, u32) = (20, 10);
+const MINIMAP_MAX_SIZE: (u32, u32) = (240, 180);
+const MINIMAP_MARGIN: u32 = 10;
+const EDGE_SCROLL_MARGIN: u32 = 16;
 
 pub fn get_bottom_text_position(resolution_y: u32) -> (u32, u32) {
     (TITLE_POSITION.0, resolution_y - 26)
 }
 
-pub fn get_tile_coordinates(id: u32, width: u32, tile_size: u32) -> (u32, u32) {
-    let x = id * tile_size % width;
-    let y = id * tile_size / width * tile_size;
-    (x, y)
-}
-
 pub fn get_block(id: u32, width: u32, tile_size: u32) -> Rect {
     let (x, y) = get_tile_coordinates(id, width, tile_size);
+    // Past the end of the texture, SDL copies nothing from there
+    let y = y.min(i32::MAX as u32);
     Rect::new(x as i32, y as i32, tile_size, tile_size)
 }
 
+/// Level tile, or tile of a texture when there is no scroll, under the
+/// screen coordinates. Scroll is in level pixels.
 pub fn get_logical_coordinates(
     graphics: &Graphics,
     x: u32,
     y: u32,
     scroll: Option<(u32, u32)>,
 ) -> (u32, u32) {
-    let render_multiplier = graphics.render_multiplier;
-    let tile_size = graphics.tile_size;
-    let scroll = scroll.unwrap_or((0, 0));
-    (
-        x / render_multiplier / tile_size + scroll.0,
-        y / render_multiplier / tile_size + scroll.1,
-    )
+    let (x, y) =
+        get_level_coordinates_from_screen_coordinates(graphics, &(x, y), &scroll.unwrap_or((0, 0)));
+    (x / graphics.tile_size, y / graphics.tile_size)
 }
 
 pub fn get_tile_id_from_coordinates(
@@ -47,108 +44,72 @@ pub fn get_tile_id_from_coordinates(
     x_logical + y_logical * x_blocks
 }
 
-pub fn get_scroll_corrected_indexes(
-    scroll: (u32, u32),
-    x_index: u32,
-    y_index: u32,
-) -> (usize, usize) {
-    ((x_index + scroll.0) as usize, (y_index + scroll.1) as usize)
-}
-
-pub fn get_absolute_coordinates_from_logical(x: u32, y: u32, render_size: u32) -> (i32, i32) {
-    (
-        (x * render_size).try_into().unwrap(),
-        (y * render_size).try_into().unwrap(),
-    )
-}
-
 pub fn get_level_coordinates_from_screen_coordinates(
     graphics: &Graphics,
     coordinates: &(u32, u32),
     scroll: &(u32, u32),
 ) -> (u32, u32) {
-    let render_multiplier = graphics.render_multiplier;
+    let render_size = graphics.get_render_size();
     let tile_size = graphics.tile_size;
+    // Tiles have a whole number of pixels at every zoom, so the ratio of the
+    // sizes scales exactly unlike the multiplier
     (
-        coordinates.0 / render_multiplier + scroll.0 * tile_size,
-        coordinates.1 / render_multiplier + scroll.1 * tile_size,
+        coordinates.0 * tile_size / render_size + scroll.0,
+        coordinates.1 * tile_size / render_size + scroll.1,
     )
 }
 
+// Rounds towards negative infinity so that neighbouring tiles are always
+// exactly one render size apart, also left of and above the viewport
 pub fn get_screen_coordinates_from_level_coordinates(
     graphics: &Graphics,
     coordinates: &(u32, u32),
     scroll: &(u32, u32),
 ) -> (i32, i32) {
-    let render_multiplier = graphics.render_multiplier;
-    let render_size = graphics.get_render_size();
+    let render_size = graphics.get_render_size() as i64;
+    let tile_size = graphics.tile_size as i64;
+    let to_screen = |coordinate: u32, scroll: u32| {
+        ((coordinate as i64 - scroll as i64) * render_size).div_euclid(tile_size) as i32
+    };
     (
-        (coordinates.0 * render_multiplier) as i32 - (scroll.0 * render_size) as i32,
-        (coordinates.1 * render_multiplier) as i32 - (scroll.1 * render_size) as i32,
+        to_screen(coordinates.0, scroll.0),
+        to_screen(coordinates.1, scroll.1),
     )
 }
 
-pub fn get_distance_between_points(p0: &(u32, u32), p1: &(u32, u32)) -> f64 {
-    let x0 = p0.0 as i32;
-    let x1 = p1.0 as i32;
-    let y0 = p0.1 as i32;
-    let y1 = p1.1 as i32;
-    (((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)) as f64).sqrt()
-}
-
-pub fn get_spotlight_render_radius(spotlight: &u8) -> u32 {
-    *spotlight as u32 * 5 + 5
-}
-
-pub fn get_steam_render_radius() -> u32 {
-    5
-}
-
-pub fn get_crate_render_size() -> u32 {
-    28
+/// Level size in level pixels.
+pub fn get_level_pixel_size(graphics: &Graphics, level_size: (u32, u32)) -> (u32, u32) {
+    (
+        level_size.0 * graphics.tile_size,
+        level_size.1 * graphics.tile_size,
+    )
 }
 
-pub fn check_box_click(
-    point_position: &(u32, u32),
-    box_position: &(u32, u32),
-    box_size: u32,
-) -> bool {
-    point_position.0 >= box_position.0
-        && point_position.0 < box_position.0 + box_size
-        && point_position.1 >= box_position.1
-        && point_position.1 < box_position.1 + box_size
+/// Level pixels visible in the window at the current zoom.
+pub fn get_viewport_pixel_size(graphics: &Graphics) -> (u32, u32) {
+    get_level_coordinates_from_screen_coordinates(
+        graphics,
+        &(graphics.resolution_x, graphics.resolution_y),
+        &(0, 0),
+    )
 }
 
-pub fn get_selected_level_tiles(
-    graphics: &Graphics,
-    p0: &(u32, u32),
-    p1: &(u32, u32),
-    x_blocks: u32,
-    scroll: Option<(u32, u32)>,
-) -> Vec<u32> {
-    let tile_ids = (
-        get_tile_id_from_coordinates(
-            graphics,
-            &(cmp::min(p0.0, p1.0), cmp::min(p0.1, p1.1)),
-            x_blocks,
-            scroll,
-        ),
-        get_tile_id_from_coordinates(
-            graphics,
-            &(cmp::max(p0.0, p1.0), cmp::max(p0.1, p1.1)),
-            x_blocks,
-            scroll,
-        ),
-    );
-    let x_diff = (tile_ids.1 - tile_ids.0) % x_blocks + 1;
-    let y_diff = (tile_ids.1 - tile_ids.0) / x_blocks + 1;
-    let mut lines: Vec<u32> = Vec::new();
-    for y in 0..y_diff {
-        let mut line: Vec<u32> =
-            (tile_ids.0 + y * x_blocks..tile_ids.0 + x_diff + y * x_blocks).collect();
-        lines.append(&mut line);
-    }
-    lines
+/// -1 or 1 on the axes where `mouse` is close to the edge of the window,
+/// 0 elsewhere.
+pub fn get_edge_scroll_direction(graphics: &Graphics, mouse: &(u32, u32)) -> (i32, i32) {
+    let get_direction = |coordinate: u32, resolution: u32| {
+        if coordinate < EDGE_SCROLL_MARGIN {
+            -1
+        } else if coordinate + EDGE_SCROLL_MARGIN >= resolution {
+            1
+        } else {
+            0
+        }
+    };
+    (
+        get_direction(mouse.0, graphics.resolution_x),
+        get_direction(mouse.1, graphics.resolution_y),
+    )
 }
 
 pub fn limit_coordinates(coordinates: &(u32, u32), limit: &(u32, u32)) -> (u32, u32) {
@@ -158,6 +119,38 @@ pub fn limit_coordinates(coordinates: &(u32, u32), limit: &(u32, u32)) -> (u32,
     )
 }
 
+/// Bottom right corner of the window, keeps the level aspect ratio.
+pub fn get_minimap_rect(graphics: &Graphics, level_size: (u32, u32)) -> Rect {
+    let scale = f64::min(
+        MINIMAP_MAX_SIZE.0 as f64 / level_size.0 as f64,
+        MINIMAP_MAX_SIZE.1 as f64 / level_size.1 as f64,
+    );
+    let width = ((level_size.0 as f64 * scale) as u32).max(1);
+    let height = ((level_size.1 as f64 * scale) as u32).max(1);
+    Rect::new(
+        graphics.resolution_x as i32 - (width + MINIMAP_MARGIN) as i32,
+        graphics.resolution_y as i32 - (height + MINIMAP_MARGIN) as i32,
+        width,
+        height,
+    )
+}
+
+/// Level tile under `coordinates` if they are on the minimap.
+pub fn get_minimap_tile(
+    rect: &Rect,
+    level_size: (u32, u32),
+    coordinates: &(u32, u32),
+) -> Option<(u32, u32)> {
+    let point = (coordinates.0 as i32, coordinates.1 as i32);
+    if !rect.contains_point(point) {
+        return None;
+    }
+    Some((
+        ((point.0 - rect.x()) as u64 * level_size.0 as u64 / rect.width() as u64) as u32,
+        ((point.1 - rect.y()) as u64 * level_size.1 as u64 / rect.height() as u64) as u32,
+    ))
+}
+
 pub fn get_number_of_tiles_in_texture(texture: &Texture, tile_size: u32) -> u32 {
     let TextureQuery { width, height, .. } = texture.query();
     width / tile_size * height / tile_size
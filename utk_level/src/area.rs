@@ -0,0 +1,188 @@
+//! Rectangular level areas and the clipboard used to copy them around.
+
+use crate::level::{Level, Position, StaticCrateType, Steam, TILE_SIZE};
+use crate::types::{Tile, Tiles};
+use std::collections::BTreeMap;
+
+/// Rectangle of whole tiles in tile coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Area {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Area {
+    /// Area covering both corner tiles, in any order.
+    pub fn from_corners(p0: &Position, p1: &Position) -> Area {
+        Area {
+            x: p0.0.min(p1.0),
+            y: p0.1.min(p1.1),
+            width: p0.0.abs_diff(p1.0) + 1,
+            height: p0.1.abs_diff(p1.1) + 1,
+        }
+    }
+
+    /// Smallest area covering all of the tiles, if there are any.
+    pub fn from_tiles(tiles: impl IntoIterator<Item = Position>) -> Option<Area> {
+        tiles.into_iter().fold(None, |area: Option<Area>, tile| {
+            Some(match area {
+                Some(area) => Area::from_corners(
+                    &(area.x.min(tile.0), area.y.min(tile.1)),
+                    &(
+                        (area.x + area.width - 1).max(tile.0),
+                        (area.y + area.height - 1).max(tile.1),
+                    ),
+                ),
+                None => Area::from_corners(&tile, &tile),
+            })
+        })
+    }
+
+    /// Tile coordinates of the area row by row.
+    pub fn get_tiles(&self) -> Vec<Position> {
+        (self.y..self.y + self.height)
+            .flat_map(|y| (self.x..self.x + self.width).map(move |x| (x, y)))
+            .collect()
+    }
+
+    pub fn contains_tile(&self, tile: &Position) -> bool {
+        tile.0 >= self.x
+            && tile.0 < self.x + self.width
+            && tile.1 >= self.y
+            && tile.1 < self.y + self.height
+    }
+
+    /// True if level pixel coordinates fall on a tile of the area.
+    pub fn contains_pixel(&self, coordinates: &Position) -> bool {
+        self.contains_tile(&(coordinates.0 / TILE_SIZE, coordinates.1 / TILE_SIZE))
+    }
+
+    pub fn get_pixel_origin(&self) -> Position {
+        (self.x * TILE_SIZE, self.y * TILE_SIZE)
+    }
+}
+
+/// Copied tiles with their shadows and the objects on top of them. Object
+/// coordinates are pixels relative to the top left corner of the copy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clipboard {
+    pub tiles: Tiles,
+    pub spotlights: BTreeMap<Position, u8>,
+    pub steams: BTreeMap<Position, Steam>,
+    pub crates: BTreeMap<Position, StaticCrateType>,
+}
+
+impl Clipboard {
+    pub fn get_size(&self) -> (u32, u32) {
+        (
+            self.tiles.first().map_or(0, |row| row.len() as u32),
+            self.tiles.len() as u32,
+        )
+    }
+}
+
+fn copy_objects<T: Copy>(objects: &BTreeMap<Position, T>, area: &Area) -> BTreeMap<Position, T> {
+    let origin = area.get_pixel_origin();
+    objects
+        .iter()
+        .filter(|(coordinates, _)| area.contains_pixel(coordinates))
+        .map(|(coordinates, object)| {
+            (
+                (coordinates.0 - origin.0, coordinates.1 - origin.1),
+                *object,
+            )
+        })
+        .collect()
+}
+
+fn paste_objects<T: Copy>(
+    objects: &mut BTreeMap<Position, T>,
+    pasted: &BTreeMap<Position, T>,
+    area: &Area,
+) {
+    let origin = area.get_pixel_origin();
+    objects.retain(|coordinates, _| !area.contains_pixel(coordinates));
+    for (coordinates, object) in pasted {
+        let coordinates = (coordinates.0 + origin.0, coordinates.1 + origin.1);
+        if area.contains_pixel(&coordinates) {
+            objects.insert(coordinates, *object);
+        }
+    }
+}
+
+impl Level {
+    /// Part of `area` that is inside the level.
+    pub fn limit_area(&self, area: &Area) -> Area {
+        let (x_size, y_size) = (self.tiles[0].len() as u32, self.tiles.len() as u32);
+        let x = area.x.min(x_size);
+        let y = area.y.min(y_size);
+        Area {
+            x,
+            y,
+            width: area.width.min(x_size - x),
+            height: area.height.min(y_size - y),
+        }
+    }
+
+    pub fn copy_area(&self, area: &Area) -> Clipboard {
+        let area = self.limit_area(area);
+        Clipboard {
+            tiles: self.tiles[area.y as usize..(area.y + area.height) as usize]
+                .iter()
+                .map(|row| row[area.x as usize..(area.x + area.width) as usize].to_vec())
+                .collect(),
+            spotlights: copy_objects(&self.spotlights, &area),
+            steams: copy_objects(&self.steams, &area),
+            crates: copy_objects(&self.crates.staticc, &area),
+        }
+    }
+
+    /// Fills the area with `fill` and removes the objects inside it.
+    pub fn clear_area(&mut self, area: &Area, fill: &Tile) {
+        let area = self.limit_area(area);
+        for row in &mut self.tiles[area.y as usize..(area.y + area.height) as usize] {
+            row[area.x as usize..(area.x + area.width) as usize].fill(*fill);
+        }
+        self.spotlights
+            .retain(|coordinates, _| !area.contains_pixel(coordinates));
+        self.steams
+            .retain(|coordinates, _| !area.contains_pixel(coordinates));
+        self.crates
+            .staticc
+            .retain(|coordinates, _| !area.contains_pixel(coordinates));
+    }
+
+    pub fn cut_area(&mut self, area: &Area, fill: &Tile) -> Clipboard {
+        let clipboard = self.copy_area(area);
+        self.clear_area(area, fill);
+        clipboard
+    }
+
+    /// Replaces tiles and objects with the clipboard content so that its top
+    /// left corner lands on `position` tile. Whatever falls outside of the
+    /// level is left out.
+    pub fn paste(&mut self, clipboard: &Clipboard, position: &Position) {
+        let (width, height) = clipboard.get_size();
+        let area = self.limit_area(&Area {
+            x: position.0,
+            y: position.1,
+            width,
+            height,
+        });
+        for (y, row) in clipboard
+            .tiles
+            .iter()
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let level_row = &mut self.tiles[area.y as usize + y];
+            level_row[area.x as usize..(area.x + area.width) as usize]
+                .copy_from_slice(&row[..area.width as usize]);
+        }
+        paste_objects(&mut self.spotlights, &clipboard.spotlights, &area);
+        paste_objects(&mut self.steams, &clipboard.steams, &area);
+        paste_objects(&mut self.crates.staticc, &clipboard.crates, &area);
+    }
+}
@@ -0,0 +1,194 @@
+//! Picks wall pieces from the walls around them using the rule table in
+//! `assets/WALLS1_RULES.TXT`.
+
+use crate::area::Area;
+use crate::level::Level;
+use crate::types::TextureType;
+use std::io;
+
+// Sides of a wall tile that face floor or the level edge
+const TOP: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 4;
+const RIGHT: usize = 8;
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    // 1-based line number and what is wrong on it
+    Syntax(usize, String),
+    MissingRules(String),
+}
+
+impl std::fmt::Display for RulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RulesError::Io(error) => write!(f, "{}", error),
+            RulesError::Syntax(line, message) => write!(f, "line {}: {}", line, message),
+            RulesError::MissingRules(name) => {
+                write!(f, "[{}] does not have all 16 side combinations", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl From<io::Error> for RulesError {
+    fn from(error: io::Error) -> Self {
+        RulesError::Io(error)
+    }
+}
+
+/// Wall pieces that fit together, one for each combination of open sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WallSet {
+    pub name: String,
+    ids: [Option<u32>; 16],
+}
+
+impl WallSet {
+    pub fn contains(&self, id: u32) -> bool {
+        self.ids.contains(&Some(id))
+    }
+
+    /// Piece with walls on every side.
+    pub fn get_inner_id(&self) -> u32 {
+        self.get_id(0)
+    }
+
+    fn get_id(&self, open_sides: usize) -> u32 {
+        self.ids[open_sides].expect("checked when parsing")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoTileRules {
+    pub sets: Vec<WallSet>,
+}
+
+fn parse_sides(text: &str) -> Option<usize> {
+    if text == "-" {
+        return Some(0);
+    }
+    let mut sides = 0;
+    for character in text.chars() {
+        let side = match character {
+            'T' => TOP,
+            'B' => BOTTOM,
+            'L' => LEFT,
+            'R' => RIGHT,
+            _ => return None,
+        };
+        if sides & side != 0 {
+            return None;
+        }
+        sides |= side;
+    }
+    Some(sides)
+}
+
+impl AutoTileRules {
+    pub fn load(filename: &str) -> Result<AutoTileRules, RulesError> {
+        AutoTileRules::parse(&std::fs::read_to_string(filename)?)
+    }
+
+    pub fn parse(text: &str) -> Result<AutoTileRules, RulesError> {
+        let mut sets: Vec<WallSet> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sets.push(WallSet {
+                    name: name.to_string(),
+                    ids: [None; 16],
+                });
+                continue;
+            }
+            let syntax_error = |message: &str| RulesError::Syntax(line_number, message.into());
+            let set = sets
+                .last_mut()
+                .ok_or_else(|| syntax_error("rule before the first [name]"))?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [sides, id] = fields[..] else {
+                return Err(syntax_error("expected sides and tile id"));
+            };
+            let sides = parse_sides(sides).ok_or_else(|| syntax_error("invalid sides"))?;
+            let id = id.parse().map_err(|_| syntax_error("invalid tile id"))?;
+            if set.ids[sides].replace(id).is_some() {
+                return Err(syntax_error("sides defined twice"));
+            }
+        }
+        if let Some(set) = sets.iter().find(|set| set.ids.contains(&None)) {
+            return Err(RulesError::MissingRules(set.name.clone()));
+        }
+        Ok(AutoTileRules { sets })
+    }
+
+    /// Set the wall piece `id` belongs to, if any.
+    pub fn get_set(&self, id: u32) -> Option<&WallSet> {
+        self.sets.iter().find(|set| set.contains(id))
+    }
+}
+
+impl Level {
+    fn is_wall(&self, x: i64, y: i64) -> bool {
+        // Outside of the level counts as floor, like in the default level border
+        y >= 0
+            && x >= 0
+            && self
+                .tiles
+                .get(y as usize)
+                .and_then(|row| row.get(x as usize))
+                .is_some_and(|tile| tile.texture_type == TextureType::WALLS)
+    }
+
+    fn get_open_sides(&self, x: usize, y: usize) -> usize {
+        let (x, y) = (x as i64, y as i64);
+        [
+            (TOP, (x, y - 1)),
+            (BOTTOM, (x, y + 1)),
+            (LEFT, (x - 1, y)),
+            (RIGHT, (x + 1, y)),
+        ]
+        .into_iter()
+        .filter(|(_, (x, y))| !self.is_wall(*x, *y))
+        .map(|(side, _)| side)
+        .sum()
+    }
+
+    /// Picks matching pieces for the walls in `area` and the tiles next to
+    /// it. Run after tiles of the area change, like [`Level::create_shadows`].
+    pub fn auto_tile_area(&mut self, rules: &AutoTileRules, area: &Area) {
+        let area = self.limit_area(&Area {
+            x: area.x.saturating_sub(1),
+            y: area.y.saturating_sub(1),
+            width: area.width + 2,
+            height: area.height + 2,
+        });
+        for y in area.y as usize..(area.y + area.height) as usize {
+            for x in area.x as usize..(area.x + area.width) as usize {
+                let tile = self.tiles[y][x];
+                if tile.texture_type != TextureType::WALLS {
+                    continue;
+                }
+                if let Some(set) = rules.get_set(tile.id) {
+                    self.tiles[y][x].id = set.get_id(self.get_open_sides(x, y));
+                }
+            }
+        }
+    }
+
+    pub fn auto_tile(&mut self, rules: &AutoTileRules) {
+        let area = Area {
+            x: 0,
+            y: 0,
+            width: self.tiles[0].len() as u32,
+            height: self.tiles.len() as u32,
+        };
+        self.auto_tile_area(rules, &area);
+    }
+}
@@ -0,0 +1,365 @@
+//! Headless tool for inspecting, validating and editing levels without SDL.
+
+use std::process::ExitCode;
+use utk_level::autotile::AutoTileRules;
+use utk_level::generate::{generate_level, GenerateError, GeneratorOptions};
+use utk_level::level::{StaticCrate, OLDEST_VERSION, VERSION};
+use utk_level::render::{render_level, Assets, RenderOptions};
+use utk_level::Level;
+
+const USAGE: &str = "usage:
+  utk-level info <LEVEL>
+  utk-level validate <LEVEL>...
+  utk-level convert <LEVEL> <OUTPUT> --to-version <1-5>
+  utk-level set <LEVEL> [--output <OUTPUT>] <KEY=VALUE>...
+  utk-level render <LEVEL> <OUTPUT.PNG> [--scale <N>] [--lighting] [--assets <DIR>]
+  utk-level generate <OUTPUT> [--seed <N>] [--size <WxH>] [--rooms <N>]
+      [--corridor-width <N>] [--wall-thickness <N>] [--spotlights] [--crates]
+      [--assets <DIR>]
+
+set keys:
+  comment=<text>
+  time_limit=<seconds>
+  enemies=<a,b,...> or enemies.<index>=<amount>
+  normal.weapons, normal.bullets, normal.energy
+  deathmatch.weapons, deathmatch.bullets, deathmatch.energy
+    tables take a full comma separated list or a single <index>=<amount>
+
+render defaults to scale 1 and the ./assets directory of the editor, --lighting
+darkens the level outside the light of its spotlights
+generate defaults to a random seed, 32x22, 5 rooms, corridor width 2 and
+wall thickness 1, the same seed and options always give the same level";
+
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("info") => info(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("set") => set(&args[1..]),
+        Some("render") => render(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(CliError::Usage(format!("unknown command '{}'", command))),
+        None => Err(CliError::Usage("missing command".to_string())),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn load(filename: &str) -> Result<(Level, u32), CliError> {
+    let file = std::fs::File::open(filename)
+        .map_err(|e| CliError::Failed(format!("{}: {}", filename, e)))?;
+    Level::read_with_version_from(std::io::BufReader::new(file))
+        .map_err(|e| CliError::Failed(format!("{}: {}", filename, e)))
+}
+
+fn save(level: &Level, filename: &str, version: u32) -> Result<(), CliError> {
+    level
+        .serialize_as_version(filename, version)
+        .map_err(|e| CliError::Failed(format!("{}: {}", filename, e)))
+}
+
+// Removes "--name value" from the arguments and returns the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, CliError> {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) if index + 1 < args.len() => {
+            let value = args.remove(index + 1);
+            args.remove(index);
+            Ok(Some(value))
+        }
+        Some(_) => Err(CliError::Usage(format!("{} needs a value", name))),
+        None => Ok(None),
+    }
+}
+
+// Removes "--name" from the arguments and tells if it was there
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, CliError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid {} '{}'", what, value)))
+}
+
+fn info(args: &[String]) -> Result<(), CliError> {
+    let [filename] = args else {
+        return Err(CliError::Usage("info takes one level".to_string()));
+    };
+    let (level, version) = load(filename)?;
+    let count_crates = |variant| {
+        level
+            .crates
+            .staticc
+            .values()
+            .filter(|crate_item| crate_item.crate_variant == variant)
+            .count()
+    };
+    println!("version: {}", version);
+    println!("size: {}x{}", level.tiles[0].len(), level.tiles.len());
+    println!("comment: {}", level.general_info.comment);
+    println!("time limit: {}", level.general_info.time_limit);
+    println!(
+        "player starts: {},{} {},{}",
+        level.p1_position.0, level.p1_position.1, level.p2_position.0, level.p2_position.1
+    );
+    println!("spotlights: {}", level.spotlights.len());
+    println!("steams: {}", level.steams.len());
+    println!("normal crates: {}", count_crates(StaticCrate::Normal));
+    println!(
+        "deathmatch crates: {}",
+        count_crates(StaticCrate::Deathmatch)
+    );
+    Ok(())
+}
+
+fn validate(args: &[String]) -> Result<(), CliError> {
+    if args.is_empty() {
+        return Err(CliError::Usage(
+            "validate takes at least one level".to_string(),
+        ));
+    }
+    let mut failures = 0;
+    for filename in args {
+        // A level that loads must also be writable back in its own version
+        let result = load(filename).and_then(|(level, version)| {
+            level
+                .write_to_version(std::io::sink(), version)
+                .map_err(|e| CliError::Failed(format!("{}: {}", filename, e)))
+        });
+        match result {
+            Ok(()) => println!("{}: ok", filename),
+            Err(CliError::Failed(message)) | Err(CliError::Usage(message)) => {
+                println!("{}", message);
+                failures += 1;
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(CliError::Failed(format!("{} invalid level(s)", failures)));
+    }
+    Ok(())
+}
+
+fn convert(args: &[String]) -> Result<(), CliError> {
+    let mut args = args.to_vec();
+    let version = match take_option(&mut args, "--to-version")? {
+        Some(version) => parse_number(&version, "version")?,
+        None => return Err(CliError::Usage("convert needs --to-version".to_string())),
+    };
+    if !(OLDEST_VERSION..=VERSION).contains(&version) {
+        return Err(CliError::Usage(format!("unsupported version {}", version)));
+    }
+    let [input, output] = &args[..] else {
+        return Err(CliError::Usage(
+            "convert takes an input and an output level".to_string(),
+        ));
+    };
+    let (level, _) = load(input)?;
+    for loss in level.get_version_losses(version) {
+        eprintln!("warning: version {} drops {}", version, loss);
+    }
+    save(&level, output, version)
+}
+
+fn set(args: &[String]) -> Result<(), CliError> {
+    let mut args = args.to_vec();
+    let output = take_option(&mut args, "--output")?;
+    let Some((filename, assignments)) = args.split_first() else {
+        return Err(CliError::Usage("set takes a level".to_string()));
+    };
+    if assignments.is_empty() {
+        return Err(CliError::Usage(
+            "set needs at least one KEY=VALUE".to_string(),
+        ));
+    }
+    let (mut level, version) = load(filename)?;
+    for assignment in assignments {
+        let Some((key, value)) = assignment.split_once('=') else {
+            return Err(CliError::Usage(format!(
+                "expected KEY=VALUE, got '{}'",
+                assignment
+            )));
+        };
+        apply_setting(&mut level, key, value)?;
+    }
+    for loss in level.get_version_losses(version) {
+        eprintln!("warning: version {} drops {}", version, loss);
+    }
+    save(&level, output.as_deref().unwrap_or(filename), version)
+}
+
+fn render(args: &[String]) -> Result<(), CliError> {
+    let mut args = args.to_vec();
+    let scale = match take_option(&mut args, "--scale")? {
+        Some(scale) => parse_number(&scale, "scale")?,
+        None => 1,
+    };
+    if !(1..=8).contains(&scale) {
+        return Err(CliError::Usage(format!("scale {} out of range 1-8", scale)));
+    }
+    let lighting = take_flag(&mut args, "--lighting");
+    let assets = take_option(&mut args, "--assets")?.unwrap_or("./assets".to_string());
+    let [input, output] = &args[..] else {
+        return Err(CliError::Usage(
+            "render takes a level and an output image".to_string(),
+        ));
+    };
+    let (level, _) = load(input)?;
+    let assets = Assets::load(&assets).map_err(|e| CliError::Failed(e.to_string()))?;
+    render_level(&level, &assets, &RenderOptions { scale, lighting })
+        .map_err(|e| CliError::Failed(e.to_string()))?
+        .save_png(output)
+        .map_err(|e| CliError::Failed(format!("{}: {}", output, e)))
+}
+
+fn generate(args: &[String]) -> Result<(), CliError> {
+    let mut args = args.to_vec();
+    let defaults = GeneratorOptions::default();
+    let mut take_number = |name: &str, what: &str, default: u32| match take_option(&mut args, name)?
+    {
+        Some(value) => parse_number(&value, what),
+        None => Ok(default),
+    };
+    let rooms = take_number("--rooms", "room count", defaults.rooms)?;
+    let corridor_width = take_number(
+        "--corridor-width",
+        "corridor width",
+        defaults.corridor_width,
+    )?;
+    let wall_thickness = take_number(
+        "--wall-thickness",
+        "wall thickness",
+        defaults.wall_thickness,
+    )?;
+    let seed = match take_option(&mut args, "--seed")? {
+        Some(seed) => parse_number(&seed, "seed")?,
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.subsec_nanos() as u64),
+    };
+    let size = match take_option(&mut args, "--size")? {
+        Some(size) => {
+            let Some((x, y)) = size.split_once('x') else {
+                return Err(CliError::Usage(format!("invalid size '{}'", size)));
+            };
+            (parse_number(x, "width")?, parse_number(y, "height")?)
+        }
+        None => defaults.size,
+    };
+    let options = GeneratorOptions {
+        size,
+        rooms,
+        corridor_width,
+        wall_thickness,
+        spotlights: take_flag(&mut args, "--spotlights"),
+        crates: take_flag(&mut args, "--crates"),
+    };
+    let assets = take_option(&mut args, "--assets")?.unwrap_or("./assets".to_string());
+    let [output] = &args[..] else {
+        return Err(CliError::Usage(
+            "generate takes an output level".to_string(),
+        ));
+    };
+    let rules_filename = format!("{}/WALLS1_RULES.TXT", assets);
+    let rules = AutoTileRules::load(&rules_filename)
+        .map_err(|e| CliError::Failed(format!("{}: {}", rules_filename, e)))?;
+    let level = generate_level(seed, &options, &rules).map_err(|e| match e {
+        GenerateError::NotEnoughSpace | GenerateError::NoWallRules => {
+            CliError::Failed(e.to_string())
+        }
+        _ => CliError::Usage(e.to_string()),
+    })?;
+    save(&level, output, VERSION)?;
+    println!("seed: {}", seed);
+    Ok(())
+}
+
+fn apply_setting(level: &mut Level, key: &str, value: &str) -> Result<(), CliError> {
+    let mut parts = key.split('.');
+    let (name, field, index) = (parts.next(), parts.next(), parts.next());
+    match (name, field, index) {
+        (Some("comment"), None, None) => level.general_info.comment = value.to_string(),
+        (Some("time_limit"), None, None) => {
+            level.general_info.time_limit = parse_number(value, "time limit")?
+        }
+        (Some("enemies"), index, None) => {
+            set_table(&mut level.general_info.enemy_table, index, value)?
+        }
+        (Some(game_type @ ("normal" | "deathmatch")), Some(field), index) => {
+            let crate_set = if game_type == "normal" {
+                &mut level.crates.random.normal
+            } else {
+                &mut level.crates.random.deathmatch
+            };
+            match (field, index) {
+                ("weapons", index) => set_table(&mut crate_set.weapons, index, value)?,
+                ("bullets", index) => set_table(&mut crate_set.bullets, index, value)?,
+                ("energy", None) => crate_set.energy = parse_number(value, "amount")?,
+                _ => return Err(CliError::Usage(format!("unknown key '{}'", key))),
+            }
+        }
+        _ => return Err(CliError::Usage(format!("unknown key '{}'", key))),
+    }
+    if parts.next().is_some() {
+        return Err(CliError::Usage(format!("unknown key '{}'", key)));
+    }
+    Ok(())
+}
+
+fn set_table(table: &mut [u32], index: Option<&str>, value: &str) -> Result<(), CliError> {
+    match index {
+        Some(index) => {
+            let index: usize = parse_number(index, "index")?;
+            let Some(amount) = table.get_mut(index) else {
+                return Err(CliError::Usage(format!(
+                    "index {} out of range 0-{}",
+                    index,
+                    table.len() - 1
+                )));
+            };
+            *amount = parse_number(value, "amount")?;
+        }
+        None => {
+            let amounts = value
+                .split(',')
+                .map(|amount| parse_number(amount, "amount"))
+                .collect::<Result<Vec<u32>, _>>()?;
+            if amounts.len() != table.len() {
+                return Err(CliError::Usage(format!(
+                    "expected {} amounts, got {}",
+                    table.len(),
+                    amounts.len()
+                )));
+            }
+            table.copy_from_slice(&amounts);
+        }
+    }
+    Ok(())
+}
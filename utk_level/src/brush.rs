@@ -0,0 +1,97 @@
+//! Brush footprints for painting and erasing several tiles at once.
+
+use crate::area::Area;
+use crate::level::{Level, Position, TILE_SIZE};
+use crate::types::Tile;
+use std::collections::BTreeSet;
+
+pub const MAX_BRUSH_SIZE: u32 = 15;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrushShape {
+    Square,
+    Round,
+}
+
+/// Square or round footprint, `size` tiles across.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Brush {
+    pub size: u32,
+    pub shape: BrushShape,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Brush {
+            size: 1,
+            shape: BrushShape::Square,
+        }
+    }
+}
+
+impl Brush {
+    /// Tiles under the brush when its middle is on `center`. Even sized
+    /// brushes reach further to the right and down.
+    pub fn get_tiles(&self, center: &Position) -> Vec<Position> {
+        let size = self.size.clamp(1, MAX_BRUSH_SIZE) as i64;
+        let middle = (size - 1) as f64 / 2.0;
+        // Slightly less than half the size rounds off the corners of small
+        // brushes too
+        let radius = size as f64 / 2.0 - 0.25;
+        let first = (
+            center.0 as i64 - (size - 1) / 2,
+            center.1 as i64 - (size - 1) / 2,
+        );
+        let mut tiles = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let is_inside = match self.shape {
+                    BrushShape::Square => true,
+                    BrushShape::Round => {
+                        (x as f64 - middle).powi(2) + (y as f64 - middle).powi(2) <= radius * radius
+                    }
+                };
+                let tile = (first.0 + x, first.1 + y);
+                if is_inside && tile.0 >= 0 && tile.1 >= 0 {
+                    tiles.push((tile.0 as u32, tile.1 as u32));
+                }
+            }
+        }
+        tiles
+    }
+}
+
+impl Level {
+    /// Replaces the `tiles` with `fill` without shadows and, if asked,
+    /// removes the objects on them. Returns the area of the tiles that
+    /// changed.
+    pub fn erase(&mut self, tiles: &[Position], fill: &Tile, remove_objects: bool) -> Option<Area> {
+        let (x_size, y_size) = (self.tiles[0].len() as u32, self.tiles.len() as u32);
+        let tiles: BTreeSet<Position> = tiles
+            .iter()
+            .filter(|(x, y)| *x < x_size && *y < y_size)
+            .copied()
+            .collect();
+        let fill = Tile { shadow: 0, ..*fill };
+        let mut changed = Vec::new();
+        for &(x, y) in &tiles {
+            let tile = &mut self.tiles[y as usize][x as usize];
+            if *tile != fill {
+                *tile = fill;
+                changed.push((x, y));
+            }
+        }
+        if remove_objects {
+            let is_kept = |coordinates: &Position| {
+                !tiles.contains(&(coordinates.0 / TILE_SIZE, coordinates.1 / TILE_SIZE))
+            };
+            self.spotlights
+                .retain(|coordinates, _| is_kept(coordinates));
+            self.steams.retain(|coordinates, _| is_kept(coordinates));
+            self.crates
+                .staticc
+                .retain(|coordinates, _| is_kept(coordinates));
+        }
+        Area::from_tiles(changed)
+    }
+}
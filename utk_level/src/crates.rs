@@ -1,4 +1,4 @@
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CrateClass {
     Weapon = 0,
     Bullet = 1,
@@ -6,12 +6,12 @@ pub enum CrateClass {
 }
 
 impl CrateClass {
-    pub fn from_u32(value: u32) -> CrateClass {
+    pub fn from_u32(value: u32) -> Option<CrateClass> {
         match value {
-            0 => CrateClass::Weapon,
-            1 => CrateClass::Bullet,
-            2 => CrateClass::Energy,
-            _ => panic!("Unknown value: {}", value),
+            0 => Some(CrateClass::Weapon),
+            1 => Some(CrateClass::Bullet),
+            2 => Some(CrateClass::Energy),
+            _ => None,
         }
     }
 }
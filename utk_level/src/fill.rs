@@ -0,0 +1,127 @@
+//! Bucket fill of connected tiles.
+
+use crate::area::Area;
+use crate::level::{Level, Position};
+use crate::types::{TextureType, Tile};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Connectivity {
+    /// Spreads to tiles sharing an edge.
+    Four,
+    /// Spreads also to diagonal neighbours.
+    Eight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FillOptions {
+    pub connectivity: Connectivity,
+    /// Fills whole rooms: spreads over floor tiles of any id up to the walls
+    /// and does nothing when started on a wall.
+    pub floors_only: bool,
+}
+
+impl Default for FillOptions {
+    fn default() -> Self {
+        FillOptions {
+            connectivity: Connectivity::Four,
+            floors_only: false,
+        }
+    }
+}
+
+impl FillOptions {
+    fn matches(&self, start: &Tile, tile: &Tile) -> bool {
+        if self.floors_only {
+            tile.texture_type == TextureType::FLOOR
+        } else {
+            tile.texture_type == start.texture_type && tile.id == start.id
+        }
+    }
+}
+
+impl Level {
+    /// Tiles connected to `position` that look the same as it, including
+    /// `position` itself.
+    pub fn get_fill_region(&self, position: &Position, options: &FillOptions) -> Vec<Position> {
+        let (x_size, y_size) = (self.tiles[0].len(), self.tiles.len());
+        let (x, y) = (position.0 as usize, position.1 as usize);
+        let Some(start) = self.tiles.get(y).and_then(|row| row.get(x)).copied() else {
+            return Vec::new();
+        };
+        if options.floors_only && start.texture_type != TextureType::FLOOR {
+            return Vec::new();
+        }
+
+        let mut visited = vec![vec![false; x_size]; y_size];
+        let mut region = Vec::new();
+        let mut stack = vec![(x, y)];
+        visited[y][x] = true;
+        while let Some((x, y)) = stack.pop() {
+            region.push((x as u32, y as u32));
+            for (dx, dy) in [
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ]
+            .into_iter()
+            .take(match options.connectivity {
+                Connectivity::Four => 4,
+                Connectivity::Eight => 8,
+            }) {
+                let (next_x, next_y) = (x as i64 + dx, y as i64 + dy);
+                if next_x < 0 || next_y < 0 || next_x >= x_size as i64 || next_y >= y_size as i64 {
+                    continue;
+                }
+                let (next_x, next_y) = (next_x as usize, next_y as usize);
+                if !visited[next_y][next_x] && options.matches(&start, &self.tiles[next_y][next_x])
+                {
+                    visited[next_y][next_x] = true;
+                    stack.push((next_x, next_y));
+                }
+            }
+        }
+        region
+    }
+
+    /// Puts the selected tile, or shadow, on every tile connected to
+    /// `position` that looks the same. Returns the changed area, if any.
+    pub fn flood_fill(
+        &mut self,
+        position: &Position,
+        selected_tile_id: u32,
+        selected_texture: &TextureType,
+        options: &FillOptions,
+    ) -> Option<Area> {
+        let region = self.get_fill_region(position, options);
+        self.put_tiles(&region, |_| selected_tile_id, selected_texture)
+    }
+
+    /// Puts a tile, or shadow, with the id given by `get_id` on each of the
+    /// `tiles` inside the level. Returns the area of the tiles that changed.
+    pub fn put_tiles(
+        &mut self,
+        tiles: &[Position],
+        get_id: impl Fn(&Position) -> u32,
+        selected_texture: &TextureType,
+    ) -> Option<Area> {
+        let x_size = self.tiles[0].len() as u32;
+        let y_size = self.tiles.len() as u32;
+        let mut changed = Vec::new();
+        for &(x, y) in tiles {
+            if x >= x_size || y >= y_size {
+                continue;
+            }
+            let before = self.tiles[y as usize][x as usize];
+            self.put_tile_to_level(y * x_size + x, Some(get_id(&(x, y))), selected_texture);
+            if self.tiles[y as usize][x as usize] != before {
+                changed.push((x, y));
+            }
+        }
+        Area::from_tiles(changed)
+    }
+}
@@ -0,0 +1,110 @@
+//! FN2 bitmap font used for all text in the editor and in rendered images.
+
+use std::io;
+
+const FIRST_CHARACTER_OFFSET: usize = 0x027D;
+const NUMBER_OF_CHARACTERS: usize = 92;
+pub const INDEX_OFFSET: usize = 0x21;
+pub const SPACE_WIDTH: u32 = 5;
+
+#[derive(Debug)]
+pub struct Line {
+    pub x: u8,
+    pub y: u8,
+    pub width: u8,
+}
+
+#[derive(Debug)]
+pub struct Character {
+    pub width: u32,
+    pub height: u32,
+    pub lines: Vec<Line>,
+}
+
+pub type FN2 = Vec<Character>;
+
+pub fn load_font(filename: &str) -> io::Result<FN2> {
+    parse_font(&std::fs::read(filename)?)
+}
+
+pub fn parse_font(data: &[u8]) -> io::Result<FN2> {
+    let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated font");
+    let read_u32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or_else(truncated)
+    };
+    let mut font: FN2 = Vec::new();
+    let mut offset = FIRST_CHARACTER_OFFSET;
+    while font.len() < NUMBER_OF_CHARACTERS {
+        let width = read_u32(offset)?;
+        let height = read_u32(offset + 4)?;
+        let color_bytes = read_u32(offset + 8)?;
+        let line_bytes = read_u32(offset + 12)?;
+        offset += 16 + color_bytes as usize;
+
+        let line_data = data
+            .get(offset..offset + line_bytes as usize / 3 * 3)
+            .ok_or_else(truncated)?;
+        let lines = line_data
+            .chunks(3)
+            .map(|line| Line {
+                x: line[0],
+                y: line[1],
+                width: line[2],
+            })
+            .filter(|line| line.width > 0)
+            .collect();
+        offset += line_data.len();
+
+        font.push(Character {
+            width,
+            height,
+            lines,
+        });
+    }
+    Ok(font)
+}
+
+fn get_character(font: &FN2, c: char) -> Option<&Character> {
+    (c as usize)
+        .checked_sub(INDEX_OFFSET)
+        .and_then(|index| font.get(index))
+}
+
+pub fn get_text_size(font: &FN2, text: &str) -> (u32, u32) {
+    let mut width = 0;
+    let mut height = 0;
+    for c in text.chars() {
+        match get_character(font, c) {
+            Some(character) => {
+                width += character.width;
+                height = height.max(character.height);
+            }
+            None => width += SPACE_WIDTH,
+        }
+    }
+    (width, height)
+}
+
+/// Horizontal pixel runs `(x, y, width)` making up `text`, relative to its
+/// top left corner. Characters missing from the font are drawn as spaces.
+pub fn get_text_lines(font: &FN2, text: &str) -> Vec<(u32, u32, u32)> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for c in text.chars() {
+        match get_character(font, c) {
+            Some(character) => {
+                lines.extend(
+                    character
+                        .lines
+                        .iter()
+                        .map(|line| (offset + line.x as u32, line.y as u32, line.width as u32)),
+                );
+                offset += character.width;
+            }
+            None => offset += SPACE_WIDTH,
+        }
+    }
+    lines
+}
@@ -0,0 +1,270 @@
+//! Random room-and-corridor levels for prototyping arenas.
+//!
+//! Everything is drawn from [`Random`], so the same seed and options always
+//! give the same level.
+
+use crate::area::Area;
+use crate::autotile::AutoTileRules;
+use crate::crates::{get_crates, CrateClass};
+use crate::level::{Level, Position, StaticCrate, StaticCrateType, TILE_SIZE};
+use crate::random::Random;
+use crate::resize::MIN_LEVEL_SIZE;
+use crate::types::{TextureType, Tile};
+
+const FLOOR_TILE: Tile = Tile {
+    texture_type: TextureType::FLOOR,
+    id: 0,
+    shadow: 0,
+};
+const MIN_ROOM_SIZE: u32 = 3;
+const MAX_ROOM_SIZE: u32 = 12;
+// Placement tries per requested room before giving up on it
+const ROOM_ATTEMPTS: u32 = 50;
+const MAX_CRATES_PER_ROOM: u32 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorOptions {
+    pub size: (u8, u8),
+    /// Rooms to place, fewer are placed if they do not fit.
+    pub rooms: u32,
+    pub corridor_width: u32,
+    /// Minimum wall between rooms and around the level.
+    pub wall_thickness: u32,
+    /// One spotlight in the middle of each room.
+    pub spotlights: bool,
+    /// A few static crates in each room.
+    pub crates: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            size: (32, 22),
+            rooms: 5,
+            corridor_width: 2,
+            wall_thickness: 1,
+            spotlights: false,
+            crates: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GenerateError {
+    InvalidLevelSize(u8, u8),
+    InvalidOption(&'static str, u32),
+    NoWallRules,
+    // Less than two rooms fit, players need separate rooms
+    NotEnoughSpace,
+}
+
+impl std::fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GenerateError::InvalidLevelSize(x, y) => write!(
+                f,
+                "invalid level size {}x{}, must be at least {}x{}",
+                x, y, MIN_LEVEL_SIZE.0, MIN_LEVEL_SIZE.1
+            ),
+            GenerateError::InvalidOption(name, value) => write!(f, "invalid {} {}", name, value),
+            GenerateError::NoWallRules => write!(f, "no wall rules to pick wall pieces from"),
+            GenerateError::NotEnoughSpace => write!(f, "not enough space for two rooms"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl GeneratorOptions {
+    fn validate(&self) -> Result<(), GenerateError> {
+        if (self.size.0 as u32) < MIN_LEVEL_SIZE.0 || (self.size.1 as u32) < MIN_LEVEL_SIZE.1 {
+            return Err(GenerateError::InvalidLevelSize(self.size.0, self.size.1));
+        }
+        for (name, value, range) in [
+            ("room count", self.rooms, 2..=64),
+            ("corridor width", self.corridor_width, 1..=4),
+            ("wall thickness", self.wall_thickness, 1..=4),
+        ] {
+            if !range.contains(&value) {
+                return Err(GenerateError::InvalidOption(name, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn get_center(room: &Area) -> Position {
+    (room.x + room.width / 2, room.y + room.height / 2)
+}
+
+// True if the rooms are closer than `distance` tiles to each other
+fn are_near(first: &Area, second: &Area, distance: u32) -> bool {
+    first.x < second.x + second.width + distance
+        && second.x < first.x + first.width + distance
+        && first.y < second.y + second.height + distance
+        && second.y < first.y + first.height + distance
+}
+
+fn place_rooms(random: &mut Random, options: &GeneratorOptions) -> Vec<Area> {
+    let size = (options.size.0 as u32, options.size.1 as u32);
+    let wall = options.wall_thickness;
+    let max_size = (
+        ((size.0 - 2 * wall) / 3).clamp(MIN_ROOM_SIZE, MAX_ROOM_SIZE),
+        ((size.1 - 2 * wall) / 3).clamp(MIN_ROOM_SIZE, MAX_ROOM_SIZE),
+    );
+    let mut rooms: Vec<Area> = Vec::new();
+    for _ in 0..options.rooms * ROOM_ATTEMPTS {
+        if rooms.len() as u32 == options.rooms {
+            break;
+        }
+        let width = random.range(MIN_ROOM_SIZE, max_size.0);
+        let height = random.range(MIN_ROOM_SIZE, max_size.1);
+        if width + 2 * wall > size.0 || height + 2 * wall > size.1 {
+            continue;
+        }
+        let room = Area {
+            x: random.range(wall, size.0 - wall - width),
+            y: random.range(wall, size.1 - wall - height),
+            width,
+            height,
+        };
+        if rooms.iter().all(|other| !are_near(&room, other, wall)) {
+            rooms.push(room);
+        }
+    }
+    // Corridors between neighbours from left to right keep them short
+    rooms.sort_by_key(|room| (get_center(room).0, get_center(room).1));
+    rooms
+}
+
+fn carve(level: &mut Level, area: &Area) {
+    let area = level.limit_area(area);
+    for row in &mut level.tiles[area.y as usize..(area.y + area.height) as usize] {
+        row[area.x as usize..(area.x + area.width) as usize].fill(FLOOR_TILE);
+    }
+}
+
+// L-shaped corridor from the middle of one room to the middle of the other,
+// cut to stay inside the level border
+fn carve_corridor(
+    level: &mut Level,
+    random: &mut Random,
+    (from, to): (&Area, &Area),
+    options: &GeneratorOptions,
+) {
+    let wall = options.wall_thickness;
+    let inside_end = (
+        options.size.0 as u32 - wall - 1,
+        options.size.1 as u32 - wall - 1,
+    );
+    let (start, end) = (get_center(from), get_center(to));
+    let corner = if random.chance(50) {
+        (end.0, start.1)
+    } else {
+        (start.0, end.1)
+    };
+    for (p0, p1) in [(start, corner), (corner, end)] {
+        let far_corner = (
+            (p0.0.max(p1.0) + options.corridor_width - 1).min(inside_end.0),
+            (p0.1.max(p1.1) + options.corridor_width - 1).min(inside_end.1),
+        );
+        carve(
+            level,
+            &Area::from_corners(&(p0.0.min(p1.0), p0.1.min(p1.1)), &far_corner),
+        );
+    }
+}
+
+fn get_random_tile(random: &mut Random, room: &Area) -> Position {
+    (
+        random.range(room.x, room.x + room.width - 1),
+        random.range(room.y, room.y + room.height - 1),
+    )
+}
+
+fn get_random_crate(random: &mut Random) -> StaticCrateType {
+    let crates = get_crates();
+    let class = random.range(0, crates.len() as u32 - 1);
+    StaticCrateType {
+        crate_variant: if random.chance(50) {
+            StaticCrate::Normal
+        } else {
+            StaticCrate::Deathmatch
+        },
+        crate_class: CrateClass::from_u32(class).unwrap(),
+        crate_type: random.range(0, crates[class as usize].len() as u32 - 1) as u8,
+    }
+}
+
+/// Builds a level of rooms joined by corridors. Walls are picked with
+/// `rules` from its first block of wall pieces and every room can be
+/// reached from every other room.
+pub fn generate_level(
+    seed: u64,
+    options: &GeneratorOptions,
+    rules: &AutoTileRules,
+) -> Result<Level, GenerateError> {
+    options.validate()?;
+    let wall_set = rules.sets.first().ok_or(GenerateError::NoWallRules)?;
+    let mut random = Random::new(seed);
+
+    let rooms = place_rooms(&mut random, options);
+    if rooms.len() < 2 {
+        return Err(GenerateError::NotEnoughSpace);
+    }
+
+    let mut level = Level::get_default_level(options.size);
+    let wall = Tile {
+        texture_type: TextureType::WALLS,
+        id: wall_set.get_inner_id(),
+        shadow: 0,
+    };
+    for row in &mut level.tiles {
+        row.fill(wall);
+    }
+    for room in &rooms {
+        carve(&mut level, room);
+    }
+    for pair in rooms.windows(2) {
+        carve_corridor(&mut level, &mut random, (&pair[0], &pair[1]), options);
+    }
+    level.auto_tile(rules);
+    level.create_shadows();
+
+    // Players start as far from each other as the rooms allow
+    let p1_room = random.range(0, rooms.len() as u32 - 1) as usize;
+    let p1_position = get_center(&rooms[p1_room]);
+    let p2_room = (0..rooms.len())
+        .filter(|&index| index != p1_room)
+        .max_by_key(|&index| {
+            let center = get_center(&rooms[index]);
+            center.0.abs_diff(p1_position.0).pow(2) + center.1.abs_diff(p1_position.1).pow(2)
+        })
+        .unwrap();
+    level.p1_position = p1_position;
+    level.p2_position = get_center(&rooms[p2_room]);
+
+    for room in &rooms {
+        if options.spotlights {
+            let center = get_center(room);
+            level.put_spotlight_to_level(
+                &(
+                    center.0 * TILE_SIZE + TILE_SIZE / 2,
+                    center.1 * TILE_SIZE + TILE_SIZE / 2,
+                ),
+                random.range(2, 6) as u8,
+            );
+        }
+        if options.crates {
+            for _ in 0..random.range(0, MAX_CRATES_PER_ROOM) {
+                let tile = get_random_tile(&mut random, room);
+                if tile == level.p1_position || tile == level.p2_position {
+                    continue;
+                }
+                let crate_item = get_random_crate(&mut random);
+                level.put_crate_to_level(&(tile.0 * TILE_SIZE, tile.1 * TILE_SIZE), &crate_item);
+            }
+        }
+    }
+    Ok(level)
+}
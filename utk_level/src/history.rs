@@ -0,0 +1,264 @@
+//! Undo/redo history of level edits.
+//!
+//! Each entry stores only what an edit changed, so long histories of small
+//! edits on big levels take little memory. Edits are grouped into
+//! transactions: the editor calls [`History::begin`] before handling input
+//! that can edit the level, which keeps a copy of it, and
+//! [`History::commit`] once the action is finished, e.g. when a drag ends,
+//! which compares the level against that copy.
+
+use crate::level::{GeneralInfo, Level, Position, RandomCrates, StaticCrateType, Steam};
+use crate::types::{Tile, Tiles};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+pub const DEFAULT_DEPTH: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Change<T> {
+    before: T,
+    after: T,
+}
+
+impl<T: Clone + PartialEq> Change<T> {
+    fn new(before: &T, after: &T) -> Option<Change<T>> {
+        (before != after).then(|| Change {
+            before: before.clone(),
+            after: after.clone(),
+        })
+    }
+}
+
+impl<T> Change<T> {
+    fn get(&self, undo: bool) -> &T {
+        if undo {
+            &self.before
+        } else {
+            &self.after
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TileChanges {
+    // (x, y) of each changed tile
+    Tiles(Vec<((usize, usize), Change<Tile>)>),
+    // Level size changed, keep both grids
+    Resize(Change<Tiles>),
+}
+
+type ObjectChanges<T> = Vec<(Position, Change<Option<T>>)>;
+
+/// Difference between two states of a level. Scroll position is not part of
+/// the history.
+#[derive(Debug)]
+pub struct Edit {
+    tiles: TileChanges,
+    p1_position: Option<Change<Position>>,
+    p2_position: Option<Change<Position>>,
+    spotlights: ObjectChanges<u8>,
+    steams: ObjectChanges<Steam>,
+    static_crates: ObjectChanges<StaticCrateType>,
+    general_info: Option<Change<GeneralInfo>>,
+    random_crates: Option<Change<RandomCrates>>,
+}
+
+fn get_tile_changes(before: &Tiles, after: &Tiles) -> TileChanges {
+    let same_size = before.len() == after.len()
+        && before
+            .iter()
+            .zip(after)
+            .all(|(before_row, after_row)| before_row.len() == after_row.len());
+    if !same_size {
+        return TileChanges::Resize(Change {
+            before: before.clone(),
+            after: after.clone(),
+        });
+    }
+    let mut changes = Vec::new();
+    for (y, (before_row, after_row)) in before.iter().zip(after).enumerate() {
+        for (x, (before_tile, after_tile)) in before_row.iter().zip(after_row).enumerate() {
+            if let Some(change) = Change::new(before_tile, after_tile) {
+                changes.push(((x, y), change));
+            }
+        }
+    }
+    TileChanges::Tiles(changes)
+}
+
+fn get_object_changes<T: Clone + PartialEq>(
+    before: &BTreeMap<Position, T>,
+    after: &BTreeMap<Position, T>,
+) -> ObjectChanges<T> {
+    let positions: BTreeSet<&Position> = before.keys().chain(after.keys()).collect();
+    positions
+        .into_iter()
+        .filter_map(|position| {
+            Change::new(
+                &before.get(position).cloned(),
+                &after.get(position).cloned(),
+            )
+            .map(|change| (*position, change))
+        })
+        .collect()
+}
+
+fn apply_object_changes<T: Clone>(
+    objects: &mut BTreeMap<Position, T>,
+    changes: &ObjectChanges<T>,
+    undo: bool,
+) {
+    // Remove first so that moves between positions cannot overwrite each other
+    for (position, _) in changes {
+        objects.remove(position);
+    }
+    for (position, change) in changes {
+        if let Some(object) = change.get(undo) {
+            objects.insert(*position, object.clone());
+        }
+    }
+}
+
+impl Edit {
+    pub fn new(before: &Level, after: &Level) -> Edit {
+        Edit {
+            tiles: get_tile_changes(&before.tiles, &after.tiles),
+            p1_position: Change::new(&before.p1_position, &after.p1_position),
+            p2_position: Change::new(&before.p2_position, &after.p2_position),
+            spotlights: get_object_changes(&before.spotlights, &after.spotlights),
+            steams: get_object_changes(&before.steams, &after.steams),
+            static_crates: get_object_changes(&before.crates.staticc, &after.crates.staticc),
+            general_info: Change::new(&before.general_info, &after.general_info),
+            random_crates: Change::new(&before.crates.random, &after.crates.random),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(&self.tiles, TileChanges::Tiles(changes) if changes.is_empty())
+            && self.p1_position.is_none()
+            && self.p2_position.is_none()
+            && self.spotlights.is_empty()
+            && self.steams.is_empty()
+            && self.static_crates.is_empty()
+            && self.general_info.is_none()
+            && self.random_crates.is_none()
+    }
+
+    fn apply(&self, level: &mut Level, undo: bool) {
+        match &self.tiles {
+            TileChanges::Tiles(changes) => {
+                for ((x, y), change) in changes {
+                    level.tiles[*y][*x] = *change.get(undo);
+                }
+            }
+            TileChanges::Resize(change) => level.tiles = change.get(undo).clone(),
+        }
+        if let Some(change) = &self.p1_position {
+            level.p1_position = *change.get(undo);
+        }
+        if let Some(change) = &self.p2_position {
+            level.p2_position = *change.get(undo);
+        }
+        apply_object_changes(&mut level.spotlights, &self.spotlights, undo);
+        apply_object_changes(&mut level.steams, &self.steams, undo);
+        apply_object_changes(&mut level.crates.staticc, &self.static_crates, undo);
+        if let Some(change) = &self.general_info {
+            level.general_info = change.get(undo).clone();
+        }
+        if let Some(change) = &self.random_crates {
+            level.crates.random = change.get(undo).clone();
+        }
+    }
+
+    pub fn undo(&self, level: &mut Level) {
+        self.apply(level, true);
+    }
+
+    pub fn redo(&self, level: &mut Level) {
+        self.apply(level, false);
+    }
+}
+
+pub struct History {
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    depth: usize,
+    // Level as it was when the current transaction began
+    snapshot: Option<Level>,
+}
+
+impl History {
+    /// Keeps at most `depth` edits, 0 disables the history.
+    pub fn new(depth: usize) -> History {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth,
+            snapshot: None,
+        }
+    }
+
+    /// Starts a transaction unless one is already open.
+    pub fn begin(&mut self, level: &Level) {
+        if self.snapshot.is_none() && self.depth > 0 {
+            self.snapshot = Some(level.clone());
+        }
+    }
+
+    /// Ends the open transaction. Returns true if the level changed since
+    /// [`History::begin`] and an undo step was stored.
+    pub fn commit(&mut self, level: &Level) -> bool {
+        let Some(snapshot) = self.snapshot.take() else {
+            return false;
+        };
+        let edit = Edit::new(&snapshot, level);
+        if edit.is_empty() {
+            return false;
+        }
+        self.redo.clear();
+        self.undo.push_back(edit);
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+        true
+    }
+
+    /// Reverts the latest edit, including a still open transaction.
+    pub fn undo(&mut self, level: &mut Level) -> bool {
+        self.commit(level);
+        match self.undo.pop_back() {
+            Some(edit) => {
+                edit.undo(level);
+                self.redo.push(edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, level: &mut Level) -> bool {
+        self.commit(level);
+        match self.redo.pop() {
+            Some(edit) => {
+                edit.redo(level);
+                self.undo.push_back(edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forgets everything, used when a different level is loaded or created.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.snapshot = None;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
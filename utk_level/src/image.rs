@@ -0,0 +1,165 @@
+//! Minimal RGBA image used for rendering levels without SDL.
+
+use png::{BitDepth, ColorType, Transformations};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+pub type Color = [u8; 4];
+
+pub const BLACK: Color = [0, 0, 0, 255];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Black image, or None if its pixel count does not fit in u32.
+    pub fn new(width: u32, height: u32) -> Option<Image> {
+        let pixel_count = usize::try_from(width.checked_mul(height)?).ok()?;
+        // On 32-bit targets the byte count can overflow even when this does not
+        pixel_count.checked_mul(BLACK.len())?;
+        Some(Image {
+            width,
+            height,
+            pixels: BLACK.repeat(pixel_count),
+        })
+    }
+
+    pub fn load_png(filename: &str) -> Result<Image, png::DecodingError> {
+        Image::read_png(BufReader::new(File::open(filename)?))
+    }
+
+    /// Decodes any PNG (indexed, grayscale, RGB or RGBA) into 8-bit RGBA.
+    pub fn read_png<R: Read>(reader: R) -> Result<Image, png::DecodingError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let data = &buffer[..info.buffer_size()];
+        let pixels = match info.color_type {
+            ColorType::Rgba => data.to_vec(),
+            ColorType::Rgb => data
+                .chunks(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            ColorType::GrayscaleAlpha => data
+                .chunks(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            ColorType::Grayscale => data.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            // Expanded by normalize_to_color8
+            ColorType::Indexed => unreachable!(),
+        };
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    pub fn save_png(&self, filename: &str) -> Result<(), png::EncodingError> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        self.write_png(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+
+    fn get_index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        Some((y as usize * self.width as usize + x as usize) * 4)
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Color {
+        let index = self
+            .get_index(x as i32, y as i32)
+            .expect("pixel outside image");
+        self.pixels[index..index + 4].try_into().unwrap()
+    }
+
+    /// Sets a pixel, silently ignoring coordinates outside the image.
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(index) = self.get_index(x, y) {
+            self.pixels[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    /// Draws `color` over the existing pixel using its alpha channel.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(index) = self.get_index(x, y) {
+            let alpha = color[3] as u32;
+            for (old, &new) in self.pixels[index..index + 3].iter_mut().zip(&color) {
+                *old = ((new as u32 * alpha + *old as u32 * (255 - alpha)) / 255) as u8;
+            }
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Color) {
+        for dy in 0..height as i32 {
+            for dx in 0..width as i32 {
+                self.put_pixel(x + dx, y + dy, color);
+            }
+        }
+    }
+
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Color) {
+        let (right, bottom) = (x + width as i32 - 1, y + height as i32 - 1);
+        for dx in x..=right {
+            self.put_pixel(dx, y, color);
+            self.put_pixel(dx, bottom, color);
+        }
+        for dy in y..=bottom {
+            self.put_pixel(x, dy, color);
+            self.put_pixel(right, dy, color);
+        }
+    }
+
+    pub fn draw_circle(&mut self, x_center: i32, y_center: i32, radius: u32, color: Color) {
+        // Same midpoint circle as the editor uses, https://stackoverflow.com/a/48291620
+        let diameter: i32 = radius as i32 * 2;
+        let mut x: i32 = radius as i32 - 1;
+        let mut y: i32 = 0;
+        let mut tx: i32 = 1;
+        let mut ty: i32 = 1;
+        let mut error: i32 = tx - diameter;
+
+        while x >= y {
+            for (dx, dy) in [
+                (x, -y),
+                (x, y),
+                (-x, -y),
+                (-x, y),
+                (y, -x),
+                (y, x),
+                (-y, -x),
+                (-y, x),
+            ] {
+                self.put_pixel(x_center + dx, y_center + dy, color);
+            }
+
+            if error <= 0 {
+                y += 1;
+                error += ty;
+                ty += 2;
+            }
+
+            if error > 0 {
+                x -= 1;
+                tx += 2;
+                error += tx - diameter;
+            }
+        }
+    }
+}
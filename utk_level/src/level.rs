@@ -0,0 +1,1044 @@
+use crate::crates::{get_crates, CrateClass};
+use crate::types::*;
+use crate::util::*;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+
+pub const DIFF_BULLETS: u32 = 9;
+pub const DIFF_WEAPONS: u32 = 11;
+pub const DIFF_ENEMIES: u32 = 8;
+
+pub const VERSION: u32 = 5;
+pub const OLDEST_VERSION: u32 = 1;
+
+pub type Position = (u32, u32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneralInfo {
+    pub comment: String, // max 19 characters + \0 termination
+    pub time_limit: u32,
+    pub enemy_table: [u32; DIFF_ENEMIES as usize],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Steam {
+    pub range: u8,  // 0-6
+    pub angle: u16, // 0-355 degress in 5 degree steps. 0 is downwards, direction counter clockwise.
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrateSet {
+    pub weapons: [u32; DIFF_WEAPONS as usize],
+    pub bullets: [u32; DIFF_BULLETS as usize],
+    pub energy: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomCrates {
+    pub normal: CrateSet,
+    pub deathmatch: CrateSet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StaticCrate {
+    Normal,
+    Deathmatch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StaticCrateType {
+    pub crate_variant: StaticCrate,
+    pub crate_class: CrateClass,
+    pub crate_type: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crates {
+    pub random: RandomCrates,
+    pub staticc: BTreeMap<Position, StaticCrateType>,
+}
+
+// Objects are kept ordered by coordinates so that saving the same level
+// always produces the same bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub tiles: Tiles,
+    pub p1_position: Position,
+    pub p2_position: Position,
+    pub scroll: Position,                   // editor viewport, in level pixels
+    pub spotlights: BTreeMap<Position, u8>, // 0-9 intensity
+    pub steams: BTreeMap<Position, Steam>,
+    pub general_info: GeneralInfo,
+    pub crates: Crates,
+}
+
+pub const MAX_LEVEL_SIZE: u32 = 1024;
+pub const MAX_ITEMS: u32 = 4096;
+pub const TILE_SIZE: u32 = 20;
+/// Tiles in the 320x200 floor and wall sheets
+pub const SHEET_TILES: u32 = 160;
+/// Tiles in the shadow sheet, tile shadows go from 0 (none) to this
+pub const SHADOW_TILES: u32 = 6;
+const COMMENT_LENGTH: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Section {
+    Header,
+    Tiles,
+    PlayerStarts,
+    Spotlights,
+    Steams,
+    Comment,
+    GeneralInfo,
+    RandomCrates,
+    StaticCrates,
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Section::Header => "header",
+            Section::Tiles => "tiles",
+            Section::PlayerStarts => "player starts",
+            Section::Spotlights => "spotlights",
+            Section::Steams => "steams",
+            Section::Comment => "comment",
+            Section::GeneralInfo => "general info",
+            Section::RandomCrates => "random crates",
+            Section::StaticCrates => "static crates",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum FileTypeError {
+    InvalidVersion(u32),
+    InvalidLevelSize(u32),
+    InvalidTextureType(u32),
+    InvalidTileId(u32),
+    InvalidShadow(u32),
+    InvalidPlayerPosition(u32),
+    InvalidCoordinate(u32),
+    InvalidSpotlightIntensity(u32),
+    InvalidSteamAngle(u32),
+    InvalidSteamRange(u32),
+    InvalidCrateClass(u32),
+    InvalidCrateType(u32),
+    TooManyItems(u32),
+    UnexpectedEndOfFile,
+    ReadFailed(std::io::ErrorKind),
+}
+
+impl std::fmt::Display for FileTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FileTypeError::InvalidVersion(value) => write!(f, "unsupported version {}", value),
+            FileTypeError::InvalidLevelSize(value) => write!(f, "invalid level size {}", value),
+            FileTypeError::InvalidTextureType(value) => {
+                write!(f, "invalid texture type {}", value)
+            }
+            FileTypeError::InvalidTileId(value) => write!(f, "invalid tile id {}", value),
+            FileTypeError::InvalidShadow(value) => write!(f, "invalid shadow {}", value),
+            FileTypeError::InvalidPlayerPosition(value) => {
+                write!(f, "player start {} outside level", value)
+            }
+            FileTypeError::InvalidCoordinate(value) => write!(f, "invalid coordinate {}", value),
+            FileTypeError::InvalidSpotlightIntensity(value) => {
+                write!(f, "invalid spotlight intensity {}", value)
+            }
+            FileTypeError::InvalidSteamAngle(value) => write!(f, "invalid steam angle {}", value),
+            FileTypeError::InvalidSteamRange(value) => write!(f, "invalid steam range {}", value),
+            FileTypeError::InvalidCrateClass(value) => write!(f, "invalid crate class {}", value),
+            FileTypeError::InvalidCrateType(value) => write!(f, "invalid crate type {}", value),
+            FileTypeError::TooManyItems(value) => write!(f, "too many items ({})", value),
+            FileTypeError::UnexpectedEndOfFile => f.write_str("unexpected end of file"),
+            FileTypeError::ReadFailed(kind) => write!(f, "read failed ({:?})", kind),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DeserializationError {
+    IOError(std::io::Error),
+    ContentError {
+        offset: u64,
+        section: Section,
+        error: FileTypeError,
+    },
+}
+
+impl std::fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeserializationError::IOError(e) => write!(f, "{}", e),
+            DeserializationError::ContentError {
+                offset,
+                section,
+                error,
+            } => write!(f, "{} in {} at byte {}", error, section, offset),
+        }
+    }
+}
+
+impl std::error::Error for DeserializationError {}
+
+impl From<std::io::Error> for DeserializationError {
+    fn from(e: std::io::Error) -> Self {
+        DeserializationError::IOError(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum SerializationError {
+    IOError(std::io::Error),
+    InvalidLevelSize,
+    InvalidComment,
+    InvalidPlayerPosition,
+    InvalidVersion(u32),
+}
+
+impl std::fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SerializationError::IOError(e) => write!(f, "{}", e),
+            SerializationError::InvalidLevelSize => f.write_str("invalid level size"),
+            SerializationError::InvalidPlayerPosition => f.write_str("player start outside level"),
+            SerializationError::InvalidVersion(version) => {
+                write!(f, "unsupported version {}", version)
+            }
+            SerializationError::InvalidComment => write!(
+                f,
+                "comment must be at most {} latin-1 characters",
+                COMMENT_LENGTH - 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+impl From<std::io::Error> for SerializationError {
+    fn from(e: std::io::Error) -> Self {
+        SerializationError::IOError(e)
+    }
+}
+
+// Content that a level holds but an older file format version cannot store
+#[derive(Debug, PartialEq)]
+pub enum VersionLoss {
+    StaticCrates(usize),
+    Enemies {
+        index: usize,
+        amount: u32,
+    },
+    RandomCrates {
+        deathmatch: bool,
+        class: CrateClass,
+        index: usize,
+        amount: u32,
+    },
+}
+
+impl std::fmt::Display for VersionLoss {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            VersionLoss::StaticCrates(amount) => write!(f, "{} static crates", amount),
+            VersionLoss::Enemies { index, amount } => {
+                write!(f, "{} enemies of type {}", amount, index + 1)
+            }
+            VersionLoss::RandomCrates {
+                deathmatch,
+                class,
+                index,
+                amount,
+            } => write!(
+                f,
+                "{} random {} crates in {} games",
+                amount,
+                get_crates()[*class as usize][*index],
+                if *deathmatch { "deathmatch" } else { "normal" }
+            ),
+        }
+    }
+}
+
+fn get_number_of_enemy_types(version: u32) -> usize {
+    if version >= 4 {
+        DIFF_ENEMIES as usize
+    } else {
+        DIFF_ENEMIES as usize - 1
+    }
+}
+
+fn get_number_of_weapons(version: u32) -> usize {
+    if version == 1 {
+        DIFF_WEAPONS as usize - 2
+    } else if version == 2 {
+        DIFF_WEAPONS as usize - 1
+    } else {
+        DIFF_WEAPONS as usize
+    }
+}
+
+fn get_number_of_bullets(version: u32) -> usize {
+    if version == 1 {
+        DIFF_BULLETS as usize - 2
+    } else if version == 2 {
+        DIFF_BULLETS as usize - 1
+    } else {
+        DIFF_BULLETS as usize
+    }
+}
+
+struct LevelReader<R: Read> {
+    reader: R,
+    offset: u64,
+    section: Section,
+}
+
+impl<R: Read> LevelReader<R> {
+    fn new(reader: R) -> Self {
+        LevelReader {
+            reader,
+            offset: 0,
+            section: Section::Header,
+        }
+    }
+
+    fn error_at(&self, offset: u64, error: FileTypeError) -> DeserializationError {
+        DeserializationError::ContentError {
+            offset,
+            section: self.section,
+            error,
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, DeserializationError> {
+        let value = self.reader.read_u8().map_err(|e| self.read_error(e))?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DeserializationError> {
+        let value = self
+            .reader
+            .read_u32::<LittleEndian>()
+            .map_err(|e| self.read_error(e))?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    // Reads a value and rejects it unless it passes the check. The error
+    // points at the start of the rejected value.
+    fn read_checked(
+        &mut self,
+        check: impl Fn(u32) -> bool,
+        error: impl Fn(u32) -> FileTypeError,
+    ) -> Result<u32, DeserializationError> {
+        let offset = self.offset;
+        let value = self.read_u32()?;
+        if check(value) {
+            Ok(value)
+        } else {
+            Err(self.error_at(offset, error(value)))
+        }
+    }
+
+    fn read_count(&mut self) -> Result<u32, DeserializationError> {
+        self.read_checked(|count| count <= MAX_ITEMS, FileTypeError::TooManyItems)
+    }
+
+    fn read_coordinates(&mut self) -> Result<Position, DeserializationError> {
+        let is_valid = |value| value < MAX_LEVEL_SIZE * TILE_SIZE;
+        Ok((
+            self.read_checked(is_valid, FileTypeError::InvalidCoordinate)?,
+            self.read_checked(is_valid, FileTypeError::InvalidCoordinate)?,
+        ))
+    }
+
+    fn read_error(&self, e: std::io::Error) -> DeserializationError {
+        self.error_at(
+            self.offset,
+            match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => FileTypeError::UnexpectedEndOfFile,
+                kind => FileTypeError::ReadFailed(kind),
+            },
+        )
+    }
+}
+
+impl Level {
+    pub fn get_default_level(size: (u8, u8)) -> Level {
+        let mut level = Level {
+            tiles: Level::init_default_level(size),
+            p1_position: (1, 1),
+            p2_position: (1, 3),
+            scroll: (0, 0),
+            spotlights: BTreeMap::new(),
+            steams: BTreeMap::new(),
+            general_info: GeneralInfo {
+                comment: "Rust UTK editor".to_string(),
+                time_limit: 60,
+                enemy_table: [1, 0, 0, 0, 0, 1, 0, 0],
+            },
+            crates: Crates {
+                random: RandomCrates {
+                    normal: CrateSet {
+                        weapons: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                        bullets: [1, 0, 0, 0, 0, 0, 0, 0, 0],
+                        energy: 1,
+                    },
+                    deathmatch: CrateSet {
+                        weapons: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                        bullets: [1, 0, 0, 0, 0, 0, 0, 0, 0],
+                        energy: 1,
+                    },
+                },
+                staticc: BTreeMap::new(),
+            },
+        };
+        level.create_shadows();
+        level
+    }
+
+    fn init_default_level(size: (u8, u8)) -> Tiles {
+        let level_size_x = size.0;
+        let level_size_y = size.1;
+
+        let mut tiles = Vec::new();
+
+        // First row ...
+        {
+            let mut row = Vec::new();
+            for x in 0..level_size_x {
+                row.push(if x == 0 {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 0,
+                        shadow: 0,
+                    }
+                } else if x == level_size_x - 1 {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 2,
+                        shadow: 0,
+                    }
+                } else {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 1,
+                        shadow: 0,
+                    }
+                });
+            }
+            tiles.push(row);
+        }
+
+        // .. all but final row ...
+        for _y in 1..level_size_y - 1 {
+            let mut row = Vec::new();
+
+            for x in 0..level_size_x {
+                row.push(if x == 0 || x == level_size_x - 1 {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 16,
+                        shadow: 0,
+                    }
+                } else {
+                    Tile {
+                        texture_type: TextureType::FLOOR,
+                        id: 0,
+                        shadow: 0,
+                    }
+                });
+            }
+            tiles.push(row);
+        }
+
+        // ... and final row!
+        {
+            let mut row = Vec::new();
+            for x in 0..level_size_x {
+                row.push(if x == 0 {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 32,
+                        shadow: 0,
+                    }
+                } else if x == level_size_x - 1 {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 18,
+                        shadow: 0,
+                    }
+                } else {
+                    Tile {
+                        texture_type: TextureType::WALLS,
+                        id: 1,
+                        shadow: 0,
+                    }
+                });
+            }
+            tiles.push(row);
+        }
+        tiles
+    }
+
+    fn get_tile_index(&self, pointed_tile: u32) -> (usize, usize) {
+        (
+            pointed_tile as usize % self.tiles[0].len(),
+            pointed_tile as usize / self.tiles[0].len(),
+        )
+    }
+
+    pub fn put_tile_to_level(
+        &mut self,
+        pointed_tile: u32,
+        selected_tile_id: Option<u32>,
+        selected_texture: &TextureType,
+    ) {
+        let (x, y) = self.get_tile_index(pointed_tile);
+        if y < self.tiles.len() && x < self.tiles[0].len() {
+            if *selected_texture != TextureType::SHADOW {
+                self.tiles[y][x] = Tile {
+                    texture_type: *selected_texture,
+                    id: selected_tile_id.unwrap(),
+                    shadow: self.tiles[y][x].shadow,
+                }
+            } else {
+                self.tiles[y][x].shadow = match selected_tile_id {
+                    Some(id) => id + 1,
+                    None => 0,
+                };
+            }
+        }
+    }
+
+    pub fn put_spotlight_to_level(&mut self, level_coordinates: &Position, spotlight: u8) {
+        if spotlight < 10 {
+            self.spotlights.insert(*level_coordinates, spotlight);
+        }
+    }
+
+    pub fn get_spotlight_from_level(&self, level_coordinates: &Position) -> u8 {
+        *self.spotlights.get(level_coordinates).unwrap()
+    }
+
+    pub fn delete_spotlight_if_near(
+        &mut self,
+        level_coordinates: &Position,
+        render_multiplier: f32,
+    ) {
+        let mut to_be_removed = Vec::new();
+        {
+            let distances: Vec<_> = self
+                .spotlights
+                .iter()
+                .map(|(spotlight_coordinates, &spotlight)| {
+                    let distance =
+                        get_distance_between_points(level_coordinates, spotlight_coordinates);
+                    (spotlight_coordinates, spotlight, distance)
+                })
+                .collect();
+            for spotlight in distances {
+                if get_spotlight_render_radius(&spotlight.1) as f64
+                    >= spotlight.2 * render_multiplier as f64
+                {
+                    to_be_removed.push(*spotlight.0);
+                }
+            }
+        }
+        for key in to_be_removed {
+            self.spotlights.remove(&key);
+        }
+    }
+
+    pub fn put_steam_to_level(&mut self, level_coordinates: &Position, steam: &Steam) {
+        if steam.range < 7 {
+            self.steams.insert(*level_coordinates, *steam);
+        }
+    }
+
+    pub fn get_steam_from_level(&self, level_coordinates: &Position) -> Steam {
+        *self.steams.get(level_coordinates).unwrap()
+    }
+
+    pub fn delete_steam_if_near(&mut self, level_coordinates: &Position, render_multiplier: f32) {
+        let mut to_be_removed = Vec::new();
+        {
+            let distances: Vec<_> = self
+                .steams
+                .iter()
+                .map(|(steam_coordinates, &_steam)| {
+                    let distance =
+                        get_distance_between_points(level_coordinates, steam_coordinates);
+                    (steam_coordinates, distance)
+                })
+                .collect();
+            for steam in distances {
+                if get_steam_render_radius() as f64 >= steam.1 * render_multiplier as f64 {
+                    to_be_removed.push(*steam.0);
+                }
+            }
+        }
+        for key in to_be_removed {
+            self.steams.remove(&key);
+        }
+    }
+
+    pub fn put_crate_to_level(
+        &mut self,
+        level_coordinates: &Position,
+        crate_item: &StaticCrateType,
+    ) {
+        self.crates.staticc.insert(*level_coordinates, *crate_item);
+    }
+
+    pub fn get_crate_from_level(&self, level_coordinates: &Position) -> &StaticCrateType {
+        self.crates.staticc.get(level_coordinates).unwrap()
+    }
+
+    pub fn delete_crate_if_near(&mut self, level_coordinates: &Position, render_multiplier: f32) {
+        let mut to_be_removed = Vec::new();
+        for crate_coordinates in self.crates.staticc.keys() {
+            if check_box_click(
+                level_coordinates,
+                crate_coordinates,
+                (get_crate_render_size() as f32 / render_multiplier) as u32,
+            ) {
+                to_be_removed.push(*crate_coordinates);
+            }
+        }
+        for key in to_be_removed {
+            self.crates.staticc.remove(&key);
+        }
+    }
+
+    pub fn create_shadows(&mut self) {
+        for y in (0..self.tiles.len()).rev() {
+            for x in 0..self.tiles[y].len() {
+                if self.tiles[y][x].texture_type != TextureType::WALLS {
+                    let on_right = if x < self.tiles[y].len() - 1 {
+                        self.tiles[y][x + 1].texture_type
+                    } else {
+                        TextureType::FLOOR
+                    };
+                    let on_top_right = if y > 0 && x < self.tiles[y].len() - 1 {
+                        self.tiles[y - 1][x + 1].texture_type
+                    } else {
+                        TextureType::FLOOR
+                    };
+                    let on_top = if y > 0 {
+                        self.tiles[y - 1][x].texture_type
+                    } else {
+                        TextureType::FLOOR
+                    };
+                    self.tiles[y][x].shadow = if on_top_right == TextureType::WALLS
+                        || (on_right == TextureType::WALLS && on_top == TextureType::WALLS)
+                    {
+                        1
+                    } else if on_top == TextureType::WALLS {
+                        3
+                    } else if on_right == TextureType::WALLS {
+                        2
+                    } else {
+                        0
+                    };
+                } else {
+                    self.tiles[y][x].shadow = 0;
+                }
+            }
+        }
+    }
+
+    pub fn serialize(&self, filename: &str) -> Result<(), SerializationError> {
+        self.serialize_as_version(filename, VERSION)
+    }
+
+    pub fn serialize_as_version(
+        &self,
+        filename: &str,
+        version: u32,
+    ) -> Result<(), SerializationError> {
+        // Encode fully before touching the file so that an invalid level
+        // never truncates an existing one.
+        let mut data = Vec::new();
+        self.write_to_version(&mut data, version)?;
+        std::fs::write(filename, data)?;
+        Ok(())
+    }
+
+    pub fn write_to<W: Write>(&self, file: W) -> Result<(), SerializationError> {
+        self.write_to_version(file, VERSION)
+    }
+
+    pub fn get_version_losses(&self, version: u32) -> Vec<VersionLoss> {
+        let mut losses = Vec::new();
+        if version < 5 && !self.crates.staticc.is_empty() {
+            losses.push(VersionLoss::StaticCrates(self.crates.staticc.len()));
+        }
+        for (index, &amount) in self
+            .general_info
+            .enemy_table
+            .iter()
+            .enumerate()
+            .skip(get_number_of_enemy_types(version))
+        {
+            if amount > 0 {
+                losses.push(VersionLoss::Enemies { index, amount });
+            }
+        }
+        for (deathmatch, crate_set) in [
+            (false, &self.crates.random.normal),
+            (true, &self.crates.random.deathmatch),
+        ] {
+            let dropped_weapons = crate_set
+                .weapons
+                .iter()
+                .enumerate()
+                .skip(get_number_of_weapons(version))
+                .map(|(index, &amount)| (CrateClass::Weapon, index, amount));
+            let dropped_bullets = crate_set
+                .bullets
+                .iter()
+                .enumerate()
+                .skip(get_number_of_bullets(version))
+                .map(|(index, &amount)| (CrateClass::Bullet, index, amount));
+            for (class, index, amount) in dropped_weapons.chain(dropped_bullets) {
+                if amount > 0 {
+                    losses.push(VersionLoss::RandomCrates {
+                        deathmatch,
+                        class,
+                        index,
+                        amount,
+                    });
+                }
+            }
+        }
+        losses
+    }
+
+    pub fn write_to_version<W: Write>(
+        &self,
+        mut file: W,
+        version: u32,
+    ) -> Result<(), SerializationError> {
+        if !(OLDEST_VERSION..=VERSION).contains(&version) {
+            return Err(SerializationError::InvalidVersion(version));
+        }
+        let comment = self.encode_comment()?;
+        let x_size = self.tiles.first().map_or(0, |row| row.len());
+        if x_size == 0 || self.tiles.iter().any(|row| row.len() != x_size) {
+            return Err(SerializationError::InvalidLevelSize);
+        }
+        let is_inside = |position: &Position| {
+            position.0 < x_size as u32 && position.1 < self.tiles.len() as u32
+        };
+        if !is_inside(&self.p1_position) || !is_inside(&self.p2_position) {
+            return Err(SerializationError::InvalidPlayerPosition);
+        }
+
+        file.write_all(&version.to_le_bytes())?;
+        file.write_all(&(self.tiles[0].len() as u32).to_le_bytes())?;
+        file.write_all(&(self.tiles.len() as u32).to_le_bytes())?;
+        for y in 0..(self.tiles.len()) {
+            for x in 0..self.tiles[0].len() {
+                file.write_all(&(self.tiles[y][x].texture_type as u32).to_le_bytes())?;
+                file.write_all(&self.tiles[y][x].id.to_le_bytes())?;
+                file.write_all(&self.tiles[y][x].shadow.to_le_bytes())?;
+            }
+        }
+
+        file.write_all(&(self.p1_position.0).to_le_bytes())?;
+        file.write_all(&(self.p1_position.1).to_le_bytes())?;
+        file.write_all(&(self.p2_position.0).to_le_bytes())?;
+        file.write_all(&(self.p2_position.1).to_le_bytes())?;
+
+        file.write_all(&(self.spotlights.len() as u32).to_le_bytes())?;
+
+        for (coordinates, spotlight) in &self.spotlights {
+            file.write_all(&coordinates.0.to_le_bytes())?;
+            file.write_all(&coordinates.1.to_le_bytes())?;
+            file.write_all(&(*spotlight as u32).to_le_bytes())?;
+        }
+
+        file.write_all(&(self.steams.len() as u32).to_le_bytes())?;
+
+        for (coordinates, steam) in &self.steams {
+            file.write_all(&coordinates.0.to_le_bytes())?;
+            file.write_all(&coordinates.1.to_le_bytes())?;
+            file.write_all(&(steam.angle as u32).to_le_bytes())?;
+            file.write_all(&(steam.range as u32).to_le_bytes())?;
+        }
+        file.write_all(&comment)?;
+        file.write_all(&self.general_info.time_limit.to_le_bytes())?;
+        for enemy_amount in self
+            .general_info
+            .enemy_table
+            .iter()
+            .take(get_number_of_enemy_types(version))
+        {
+            file.write_all(&enemy_amount.to_le_bytes())?;
+        }
+        for crate_set in [&self.crates.random.normal, &self.crates.random.deathmatch] {
+            for weapon_amount in crate_set
+                .weapons
+                .iter()
+                .take(get_number_of_weapons(version))
+            {
+                file.write_all(&weapon_amount.to_le_bytes())?;
+            }
+            for bullet_amount in crate_set
+                .bullets
+                .iter()
+                .take(get_number_of_bullets(version))
+            {
+                file.write_all(&bullet_amount.to_le_bytes())?;
+            }
+            file.write_all(&crate_set.energy.to_le_bytes())?;
+        }
+
+        if version < 5 {
+            return Ok(());
+        }
+
+        let normal_static_crates: BTreeMap<Position, StaticCrateType> = self
+            .crates
+            .staticc
+            .clone()
+            .into_iter()
+            .filter(|(_coordinates, crate_item)| crate_item.crate_variant == StaticCrate::Normal)
+            .collect();
+        file.write_all(&(normal_static_crates.len() as u32).to_le_bytes())?;
+        for (coordinates, crate_item) in &normal_static_crates {
+            file.write_all(&(crate_item.crate_class as u32).to_le_bytes())?;
+            file.write_all(&(crate_item.crate_type as u32).to_le_bytes())?;
+            file.write_all(&coordinates.0.to_le_bytes())?;
+            file.write_all(&coordinates.1.to_le_bytes())?;
+        }
+
+        let deathmatch_static_crates: BTreeMap<Position, StaticCrateType> = self
+            .crates
+            .staticc
+            .clone()
+            .into_iter()
+            .filter(|(_coordinates, crate_item)| {
+                crate_item.crate_variant == StaticCrate::Deathmatch
+            })
+            .collect();
+        file.write_all(&(deathmatch_static_crates.len() as u32).to_le_bytes())?;
+        for (coordinates, crate_item) in &deathmatch_static_crates {
+            file.write_all(&(crate_item.crate_class as u32).to_le_bytes())?;
+            file.write_all(&(crate_item.crate_type as u32).to_le_bytes())?;
+            file.write_all(&coordinates.0.to_le_bytes())?;
+            file.write_all(&coordinates.1.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_comment(&self) -> Result<[u8; COMMENT_LENGTH], SerializationError> {
+        // Comment is stored as latin-1 and must leave room for \0 termination
+        let mut comment = [0; COMMENT_LENGTH];
+        if self.general_info.comment.chars().count() >= COMMENT_LENGTH {
+            return Err(SerializationError::InvalidComment);
+        }
+        for (byte, c) in comment.iter_mut().zip(self.general_info.comment.chars()) {
+            *byte = match u8::try_from(u32::from(c)) {
+                Ok(value) if value != 0 => value,
+                _ => return Err(SerializationError::InvalidComment),
+            };
+        }
+        Ok(comment)
+    }
+
+    pub fn deserialize(&mut self, filename: &str) -> Result<(), DeserializationError> {
+        let file = BufReader::new(File::open(filename)?);
+        *self = Level::read_from(file)?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: R) -> Result<Level, DeserializationError> {
+        Ok(Level::read_with_version_from(reader)?.0)
+    }
+
+    // Also returns the format version the level was stored in
+    pub fn read_with_version_from<R: Read>(
+        reader: R,
+    ) -> Result<(Level, u32), DeserializationError> {
+        let mut file = LevelReader::new(reader);
+        let version = file.read_checked(
+            |version| (OLDEST_VERSION..=VERSION).contains(&version),
+            FileTypeError::InvalidVersion,
+        )?;
+        let is_valid_size = |size| (1..=MAX_LEVEL_SIZE).contains(&size);
+        let x_size = file.read_checked(is_valid_size, FileTypeError::InvalidLevelSize)?;
+        let y_size = file.read_checked(is_valid_size, FileTypeError::InvalidLevelSize)?;
+
+        file.section = Section::Tiles;
+        let mut tiles = Vec::new();
+        for _ in 0..y_size {
+            let mut row = Vec::new();
+            for _ in 0..x_size {
+                let offset = file.offset;
+                let texture_type = file.read_u32()?;
+                row.push(Tile {
+                    texture_type: match TextureType::from_u32(texture_type) {
+                        Some(texture_type) if texture_type != TextureType::SHADOW => texture_type,
+                        _ => {
+                            return Err(file
+                                .error_at(offset, FileTypeError::InvalidTextureType(texture_type)))
+                        }
+                    },
+                    id: file.read_checked(|id| id < SHEET_TILES, FileTypeError::InvalidTileId)?,
+                    shadow: file.read_checked(
+                        |shadow| shadow <= SHADOW_TILES,
+                        FileTypeError::InvalidShadow,
+                    )?,
+                });
+            }
+            tiles.push(row);
+        }
+
+        file.section = Section::PlayerStarts;
+        let mut player_positions = [(0, 0); 2];
+        for position in &mut player_positions {
+            *position = (
+                file.read_checked(|x| x < x_size, FileTypeError::InvalidPlayerPosition)?,
+                file.read_checked(|y| y < y_size, FileTypeError::InvalidPlayerPosition)?,
+            );
+        }
+
+        file.section = Section::Spotlights;
+        let mut spotlights = BTreeMap::new();
+        let spotlight_amount = file.read_count()?;
+        for _ in 0..spotlight_amount {
+            let coordinates = file.read_coordinates()?;
+            let intensity = file.read_checked(
+                |intensity| intensity < 10,
+                FileTypeError::InvalidSpotlightIntensity,
+            )?;
+            spotlights.insert(coordinates, intensity as u8);
+        }
+
+        file.section = Section::Steams;
+        let mut steams = BTreeMap::new();
+        let steam_amount = file.read_count()?;
+        for _ in 0..steam_amount {
+            let coordinates = file.read_coordinates()?;
+            let angle = file.read_checked(|angle| angle < 360, FileTypeError::InvalidSteamAngle)?;
+            let range = file.read_checked(|range| range < 7, FileTypeError::InvalidSteamRange)?;
+            steams.insert(
+                coordinates,
+                Steam {
+                    angle: angle as u16,
+                    range: range as u8,
+                },
+            );
+        }
+
+        file.section = Section::Comment;
+        let mut comment = String::new();
+        for _ in 0..COMMENT_LENGTH {
+            let c = file.read_u8()? as char;
+            if c != '\0' {
+                comment.push(c);
+            }
+        }
+
+        file.section = Section::GeneralInfo;
+        let time_limit = file.read_u32()?;
+
+        let mut enemy_table = [0; DIFF_ENEMIES as usize];
+        for enemy_amount in enemy_table
+            .iter_mut()
+            .take(get_number_of_enemy_types(version))
+        {
+            *enemy_amount = file.read_u32()?;
+        }
+
+        file.section = Section::RandomCrates;
+        let number_of_weapons = get_number_of_weapons(version);
+        let number_of_bullets = get_number_of_bullets(version);
+        let normal = Level::deserialize_crate_set(&mut file, number_of_weapons, number_of_bullets)?;
+        let deathmatch =
+            Level::deserialize_crate_set(&mut file, number_of_weapons, number_of_bullets)?;
+
+        file.section = Section::StaticCrates;
+        let mut staticc = BTreeMap::new();
+        if version >= 5 {
+            Level::deserialize_crates(&mut file, &mut staticc, StaticCrate::Normal)?;
+            Level::deserialize_crates(&mut file, &mut staticc, StaticCrate::Deathmatch)?;
+        }
+
+        let level = Level {
+            tiles,
+            p1_position: player_positions[0],
+            p2_position: player_positions[1],
+            scroll: (0, 0),
+            spotlights,
+            steams,
+            general_info: GeneralInfo {
+                comment,
+                time_limit,
+                enemy_table,
+            },
+            crates: Crates {
+                random: RandomCrates { normal, deathmatch },
+                staticc,
+            },
+        };
+        Ok((level, version))
+    }
+
+    fn deserialize_crate_set<R: Read>(
+        file: &mut LevelReader<R>,
+        number_of_weapons: usize,
+        number_of_bullets: usize,
+    ) -> Result<CrateSet, DeserializationError> {
+        let mut crate_set = CrateSet {
+            weapons: [0; DIFF_WEAPONS as usize],
+            bullets: [0; DIFF_BULLETS as usize],
+            energy: 0,
+        };
+        for weapon_amount in crate_set.weapons.iter_mut().take(number_of_weapons) {
+            *weapon_amount = file.read_u32()?;
+        }
+        for bullet_amount in crate_set.bullets.iter_mut().take(number_of_bullets) {
+            *bullet_amount = file.read_u32()?;
+        }
+        crate_set.energy = file.read_u32()?;
+        Ok(crate_set)
+    }
+
+    fn deserialize_crates<R: Read>(
+        file: &mut LevelReader<R>,
+        crates: &mut BTreeMap<Position, StaticCrateType>,
+        crate_variant: StaticCrate,
+    ) -> Result<(), DeserializationError> {
+        let number_of_crates = file.read_count()?;
+        for _crate_index in 0..number_of_crates {
+            let offset = file.offset;
+            let crate_class = file.read_u32()?;
+            let crate_class = CrateClass::from_u32(crate_class).ok_or_else(|| {
+                file.error_at(offset, FileTypeError::InvalidCrateClass(crate_class))
+            })?;
+            let number_of_types = get_crates()[crate_class as usize].len() as u32;
+            let crate_type = file.read_checked(
+                |crate_type| crate_type < number_of_types,
+                FileTypeError::InvalidCrateType,
+            )?;
+            let crate_item = StaticCrateType {
+                crate_variant,
+                crate_class,
+                crate_type: crate_type as u8,
+            };
+            crates.insert(file.read_coordinates()?, crate_item);
+        }
+
+        Ok(())
+    }
+}
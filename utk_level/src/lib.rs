@@ -0,0 +1,27 @@
+//! Level format of Ultimate Tapan Kaikki (TK321).
+//!
+//! Shared by the SDL editor and any headless tooling that needs to read,
+//! write or render `.LEV` files. Does not depend on SDL.
+
+pub mod area;
+pub mod autotile;
+pub mod brush;
+pub mod crates;
+pub mod fill;
+pub mod fn2;
+pub mod generate;
+pub mod history;
+pub mod image;
+pub mod level;
+pub mod lighting;
+pub mod objects;
+pub mod random;
+pub mod render;
+pub mod resize;
+pub mod shape;
+pub mod transform;
+pub mod types;
+pub mod util;
+pub mod variation;
+
+pub use level::Level;
@@ -0,0 +1,103 @@
+//! Rough preview of how dark the level looks in the game and how its
+//! spotlights light it up.
+
+use crate::level::{Level, TILE_SIZE};
+
+/// Light of the level where no spotlight reaches, 0 is black and 1 shows
+/// the tiles unchanged.
+pub const AMBIENT_LIGHT: f32 = 0.3;
+
+/// Distance in level pixels where the light of a spotlight has faded out.
+pub fn get_light_radius(intensity: u8) -> f32 {
+    TILE_SIZE as f32 * (1.5 + intensity as f32 * 0.5)
+}
+
+// Light added right under a spotlight, on top of the ambient light
+fn get_light_strength(intensity: u8) -> f32 {
+    (1.0 - AMBIENT_LIGHT) * (0.4 + 0.6 * intensity as f32 / 9.0)
+}
+
+/// Light levels of the level sampled every `step` level pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightMap {
+    pub width: u32,
+    pub height: u32,
+    pub step: u32,
+    /// Row by row, between `AMBIENT_LIGHT` and 1.
+    pub values: Vec<f32>,
+}
+
+impl LightMap {
+    /// Light at the level pixel, or the ambient light outside the level.
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        let (x, y) = (x / self.step, y / self.step);
+        if x < self.width && y < self.height {
+            self.values[(y * self.width + x) as usize]
+        } else {
+            AMBIENT_LIGHT
+        }
+    }
+}
+
+impl Level {
+    /// Light at the level pixel: the ambient light and the light of every
+    /// spotlight reaching it, fading out with distance.
+    pub fn get_light(&self, x: f32, y: f32) -> f32 {
+        let mut light = AMBIENT_LIGHT;
+        for (coordinates, &intensity) in &self.spotlights {
+            let distance =
+                ((x - coordinates.0 as f32).powi(2) + (y - coordinates.1 as f32).powi(2)).sqrt();
+            light += get_falloff(distance, intensity);
+        }
+        light.min(1.0)
+    }
+
+    /// Light of the whole level, sampled from the middle of each `step` by
+    /// `step` block of level pixels.
+    pub fn get_light_map(&self, step: u32) -> LightMap {
+        let step = step.max(1);
+        let x_tiles = self.tiles.first().map_or(0, |row| row.len() as u32);
+        let width = (x_tiles * TILE_SIZE).div_ceil(step);
+        let height = (self.tiles.len() as u32 * TILE_SIZE).div_ceil(step);
+        let mut values = vec![AMBIENT_LIGHT; (width * height) as usize];
+        let get_sample = |coordinate: f32| (coordinate / step as f32).max(0.0) as u32;
+        // Only the samples a spotlight can reach
+        for (coordinates, &intensity) in &self.spotlights {
+            let radius = get_light_radius(intensity);
+            let center = (coordinates.0 as f32, coordinates.1 as f32);
+            let x_range =
+                get_sample(center.0 - radius)..get_sample(center.0 + radius + 1.0).min(width);
+            for y in get_sample(center.1 - radius)..get_sample(center.1 + radius + 1.0).min(height)
+            {
+                for x in x_range.clone() {
+                    let sample = (
+                        (x * step) as f32 + step as f32 / 2.0,
+                        (y * step) as f32 + step as f32 / 2.0,
+                    );
+                    let distance =
+                        ((sample.0 - center.0).powi(2) + (sample.1 - center.1).powi(2)).sqrt();
+                    values[(y * width + x) as usize] += get_falloff(distance, intensity);
+                }
+            }
+        }
+        for value in &mut values {
+            *value = value.min(1.0);
+        }
+        LightMap {
+            width,
+            height,
+            step,
+            values,
+        }
+    }
+}
+
+// Brightest under the spotlight, smoothly reaching nothing at the radius
+fn get_falloff(distance: f32, intensity: u8) -> f32 {
+    let radius = get_light_radius(intensity);
+    if distance >= radius {
+        return 0.0;
+    }
+    let remaining = 1.0 - distance / radius;
+    get_light_strength(intensity) * remaining * remaining
+}
@@ -0,0 +1,317 @@
+//! Picking, moving and editing the spotlights, steams and static crates of
+//! a level, one at a time or many at once.
+
+use crate::crates::{get_crates, CrateClass};
+use crate::level::{Level, Position, StaticCrate, StaticCrateType, Steam, TILE_SIZE};
+use crate::util::*;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObjectKind {
+    Spotlight,
+    Steam,
+    Crate,
+}
+
+/// One object of the level, found by its kind and its key in the level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ObjectRef {
+    pub kind: ObjectKind,
+    pub position: Position,
+}
+
+/// Property change of many objects at once. Each change is for one kind of
+/// object and leaves the others as they are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectChange {
+    /// Spotlight intensity up or down, kept within 0-9
+    Intensity(i32),
+    /// Steam range up or down, kept within 0-6
+    SteamRange(i32),
+    /// Steam angle in degrees, turns around past 355
+    SteamAngle(i32),
+    /// Next or previous crate class, the crates get its first crate type
+    CrateClass(i32),
+    /// Crate type up or down, kept within the crate class
+    CrateType(i32),
+    CrateVariant(StaticCrate),
+}
+
+// Properties of an object taken out of the level while moving it
+enum ObjectValue {
+    Spotlight(u8),
+    Steam(Steam),
+    Crate(StaticCrateType),
+}
+
+impl Level {
+    /// Objects drawn under the level pixel at `render_multiplier` zoom,
+    /// topmost first: crates, then steams, then spotlights, each kind
+    /// nearest first.
+    pub fn get_objects_at(
+        &self,
+        level_coordinates: &Position,
+        render_multiplier: f32,
+    ) -> Vec<ObjectRef> {
+        let crate_size = (get_crate_render_size() as f32 / render_multiplier) as u32;
+        let mut crates: Vec<_> = self
+            .crates
+            .staticc
+            .keys()
+            .filter(|crate_coordinates| {
+                check_box_click(level_coordinates, crate_coordinates, crate_size)
+            })
+            .map(|crate_coordinates| {
+                let center = (
+                    crate_coordinates.0 + crate_size / 2,
+                    crate_coordinates.1 + crate_size / 2,
+                );
+                (
+                    ObjectKind::Crate,
+                    *crate_coordinates,
+                    get_distance_between_points(level_coordinates, &center),
+                )
+            })
+            .collect();
+        crates.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        let is_near = |coordinates: &Position, radius: u32| {
+            let distance = get_distance_between_points(level_coordinates, coordinates);
+            (radius as f64 >= distance * render_multiplier as f64).then_some(distance)
+        };
+        let mut steams: Vec<_> = self
+            .steams
+            .keys()
+            .filter_map(|coordinates| {
+                is_near(coordinates, get_steam_render_radius())
+                    .map(|distance| (ObjectKind::Steam, *coordinates, distance))
+            })
+            .collect();
+        steams.sort_by(|a, b| a.2.total_cmp(&b.2));
+        let mut spotlights: Vec<_> = self
+            .spotlights
+            .iter()
+            .filter_map(|(coordinates, spotlight)| {
+                is_near(coordinates, get_spotlight_render_radius(spotlight))
+                    .map(|distance| (ObjectKind::Spotlight, *coordinates, distance))
+            })
+            .collect();
+        spotlights.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        crates
+            .into_iter()
+            .chain(steams)
+            .chain(spotlights)
+            .map(|(kind, position, _)| ObjectRef { kind, position })
+            .collect()
+    }
+
+    pub fn contains_object(&self, object: &ObjectRef) -> bool {
+        match object.kind {
+            ObjectKind::Spotlight => self.spotlights.contains_key(&object.position),
+            ObjectKind::Steam => self.steams.contains_key(&object.position),
+            ObjectKind::Crate => self.crates.staticc.contains_key(&object.position),
+        }
+    }
+
+    /// Objects with their position inside the rectangle between two level
+    /// pixel corners, corners included.
+    pub fn get_objects_in(&self, corner: &Position, other_corner: &Position) -> Vec<ObjectRef> {
+        let x_range = corner.0.min(other_corner.0)..=corner.0.max(other_corner.0);
+        let y_range = corner.1.min(other_corner.1)..=corner.1.max(other_corner.1);
+        let is_inside =
+            |position: &Position| x_range.contains(&position.0) && y_range.contains(&position.1);
+        let spotlights = self
+            .spotlights
+            .keys()
+            .map(|position| (ObjectKind::Spotlight, position));
+        let steams = self
+            .steams
+            .keys()
+            .map(|position| (ObjectKind::Steam, position));
+        let crates = self
+            .crates
+            .staticc
+            .keys()
+            .map(|position| (ObjectKind::Crate, position));
+        spotlights
+            .chain(steams)
+            .chain(crates)
+            .filter(|(_, position)| is_inside(position))
+            .map(|(kind, position)| ObjectRef {
+                kind,
+                position: *position,
+            })
+            .collect()
+    }
+
+    /// Moves the object to another level pixel keeping its properties.
+    /// Nothing changes if the object does not exist, the pixel is outside
+    /// the level or another object of the same kind is already there.
+    /// Returns the object at its new position.
+    pub fn move_object(
+        &mut self,
+        object: &ObjectRef,
+        level_coordinates: &Position,
+    ) -> Option<ObjectRef> {
+        let delta = (
+            level_coordinates.0 as i64 - object.position.0 as i64,
+            level_coordinates.1 as i64 - object.position.1 as i64,
+        );
+        let moved = self.move_objects(&BTreeSet::from([*object]), delta)?;
+        moved.into_iter().next()
+    }
+
+    /// Moves all the objects `delta` level pixels, or none of them if one
+    /// does not exist, would leave the level or would land on an object of
+    /// the same kind that is not moving. Returns the objects at their new
+    /// positions.
+    pub fn move_objects(
+        &mut self,
+        objects: &BTreeSet<ObjectRef>,
+        delta: (i64, i64),
+    ) -> Option<BTreeSet<ObjectRef>> {
+        let mut moves = Vec::new();
+        for object in objects {
+            let x = object.position.0 as i64 + delta.0;
+            let y = object.position.1 as i64 + delta.1;
+            if !self.contains_object(object) || x < 0 || y < 0 {
+                return None;
+            }
+            let moved = ObjectRef {
+                position: (x as u32, y as u32),
+                ..*object
+            };
+            if !self.is_inside_level(&moved.position)
+                || (!objects.contains(&moved) && self.contains_object(&moved))
+            {
+                return None;
+            }
+            moves.push((*object, moved));
+        }
+        // Everything is taken out first, objects may move to where another
+        // one of them was
+        let values: Vec<_> = moves
+            .iter()
+            .map(|(object, _)| self.take_object(object))
+            .collect();
+        for ((_, moved), value) in moves.iter().zip(values) {
+            self.put_object(&moved.position, value);
+        }
+        Some(moves.into_iter().map(|(_, moved)| moved).collect())
+    }
+
+    /// Removes the objects, returns how many there were.
+    pub fn delete_objects(&mut self, objects: &BTreeSet<ObjectRef>) -> usize {
+        objects
+            .iter()
+            .filter(|object| self.take_object(object).is_some())
+            .count()
+    }
+
+    /// Changes a property of the objects it is for, returns how many of
+    /// them changed.
+    pub fn change_objects(&mut self, objects: &BTreeSet<ObjectRef>, change: ObjectChange) -> usize {
+        let crates = get_crates();
+        let mut changed = 0;
+        for object in objects {
+            let position = &object.position;
+            let is_changed = match (change, object.kind) {
+                (ObjectChange::Intensity(delta), ObjectKind::Spotlight) => {
+                    self.spotlights.get_mut(position).is_some_and(|spotlight| {
+                        let old = *spotlight;
+                        *spotlight = (*spotlight as i32 + delta).clamp(0, 9) as u8;
+                        *spotlight != old
+                    })
+                }
+                (ObjectChange::SteamRange(delta), ObjectKind::Steam) => {
+                    self.steams.get_mut(position).is_some_and(|steam| {
+                        let old = steam.range;
+                        steam.range = (steam.range as i32 + delta).clamp(0, 6) as u8;
+                        steam.range != old
+                    })
+                }
+                (ObjectChange::SteamAngle(delta), ObjectKind::Steam) => {
+                    self.steams.get_mut(position).is_some_and(|steam| {
+                        let old = steam.angle;
+                        steam.angle = (steam.angle as i32 + delta).rem_euclid(360) as u16;
+                        steam.angle != old
+                    })
+                }
+                (ObjectChange::CrateClass(delta), ObjectKind::Crate) => self
+                    .crates
+                    .staticc
+                    .get_mut(position)
+                    .is_some_and(|crate_item| {
+                        let class = (crate_item.crate_class as i32 + delta).clamp(0, 2);
+                        match CrateClass::from_u32(class as u32) {
+                            Some(crate_class) if crate_class != crate_item.crate_class => {
+                                crate_item.crate_class = crate_class;
+                                crate_item.crate_type = 0;
+                                true
+                            }
+                            _ => false,
+                        }
+                    }),
+                (ObjectChange::CrateType(delta), ObjectKind::Crate) => self
+                    .crates
+                    .staticc
+                    .get_mut(position)
+                    .is_some_and(|crate_item| {
+                        let old = crate_item.crate_type;
+                        let last = crates[crate_item.crate_class as usize].len() as i32 - 1;
+                        crate_item.crate_type = (old as i32 + delta).clamp(0, last) as u8;
+                        crate_item.crate_type != old
+                    }),
+                (ObjectChange::CrateVariant(variant), ObjectKind::Crate) => self
+                    .crates
+                    .staticc
+                    .get_mut(position)
+                    .is_some_and(|crate_item| {
+                        let old = crate_item.crate_variant;
+                        crate_item.crate_variant = variant;
+                        variant != old
+                    }),
+                _ => false,
+            };
+            changed += is_changed as usize;
+        }
+        changed
+    }
+
+    fn take_object(&mut self, object: &ObjectRef) -> Option<ObjectValue> {
+        match object.kind {
+            ObjectKind::Spotlight => self
+                .spotlights
+                .remove(&object.position)
+                .map(ObjectValue::Spotlight),
+            ObjectKind::Steam => self.steams.remove(&object.position).map(ObjectValue::Steam),
+            ObjectKind::Crate => self
+                .crates
+                .staticc
+                .remove(&object.position)
+                .map(ObjectValue::Crate),
+        }
+    }
+
+    fn put_object(&mut self, position: &Position, value: Option<ObjectValue>) {
+        match value {
+            Some(ObjectValue::Spotlight(spotlight)) => {
+                self.spotlights.insert(*position, spotlight);
+            }
+            Some(ObjectValue::Steam(steam)) => {
+                self.steams.insert(*position, steam);
+            }
+            Some(ObjectValue::Crate(crate_item)) => {
+                self.crates.staticc.insert(*position, crate_item);
+            }
+            None => (),
+        }
+    }
+
+    fn is_inside_level(&self, level_coordinates: &Position) -> bool {
+        let x_size = self.tiles.first().map_or(0, |row| row.len() as u32);
+        level_coordinates.0 < x_size * TILE_SIZE
+            && level_coordinates.1 < self.tiles.len() as u32 * TILE_SIZE
+    }
+}
@@ -0,0 +1,33 @@
+//! Small seeded random number generator. Levels generated from a seed must
+//! stay the same between releases, so the algorithm (SplitMix64) is kept
+//! here instead of depending on a crate that may change its output.
+
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// Number in `min..=max`.
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        assert!(min <= max, "empty range {}..={}", min, max);
+        min + (self.next_u64() % (max as u64 - min as u64 + 1)) as u32
+    }
+
+    /// True `percent` times out of 100.
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.range(0, 99) < percent
+    }
+}
@@ -0,0 +1,262 @@
+//! Renders levels to images the same way the editor draws them, without SDL.
+
+use crate::crates::get_crates;
+use crate::fn2::{self, FN2};
+use crate::image::{Color, Image, BLACK};
+use crate::level::{Level, StaticCrate, TILE_SIZE};
+use crate::types::TextureType;
+use crate::util::*;
+use std::io;
+
+pub const TEXT_SIZE_MULTIPLIER: u32 = 2;
+const TEXT_SHADOW_PIXELS: u32 = 1;
+
+const RED: Color = [255, 0, 0, 255];
+const BLUE: Color = [0, 0, 255, 255];
+const LIGHT_BLUE: Color = [100, 100, 255, 255];
+const LIGHT_GREEN: Color = [100, 255, 100, 255];
+const MINIMAP_FLOOR: Color = [96, 96, 96, 255];
+const MINIMAP_WALL: Color = [176, 144, 96, 255];
+
+#[derive(Debug)]
+pub enum AssetError {
+    Image(String, png::DecodingError),
+    Font(String, io::Error),
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AssetError::Image(filename, error) => write!(f, "{}: {}", filename, error),
+            AssetError::Font(filename, error) => write!(f, "{}: {}", filename, error),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// The rendered image would have more pixels than an image can hold.
+#[derive(Debug)]
+pub struct ImageSizeError {
+    pub width: u64,
+    pub height: u64,
+}
+
+impl std::fmt::Display for ImageSizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "image of {}x{} pixels is too large",
+            self.width, self.height
+        )
+    }
+}
+
+impl std::error::Error for ImageSizeError {}
+
+/// Tile sheets and font the editor loads from its assets directory.
+pub struct Assets {
+    pub floor: Image,
+    pub walls: Image,
+    pub shadows: Image,
+    pub font: FN2,
+}
+
+impl Assets {
+    pub fn load(directory: &str) -> Result<Assets, AssetError> {
+        let load_image = |name: &str| {
+            let filename = format!("{}/{}", directory, name);
+            Image::load_png(&filename).map_err(|e| AssetError::Image(filename, e))
+        };
+        let font_filename = format!("{}/TETRIS.FN2", directory);
+        Ok(Assets {
+            floor: load_image("FLOOR1.PNG")?,
+            walls: load_image("WALLS1.PNG")?,
+            shadows: load_image("SHADOWS_ALPHA.PNG")?,
+            font: fn2::load_font(&font_filename).map_err(|e| AssetError::Font(font_filename, e))?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    /// Pixels per level pixel, same as the editor render multiplier.
+    pub scale: u32,
+    /// Darkens the tiles outside the light of the spotlights.
+    pub lighting: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            scale: 1,
+            lighting: false,
+        }
+    }
+}
+
+/// Renders the whole level: tiles, shadows, spotlights, steams, static
+/// crates and player starts. Markers and labels keep the size they have in
+/// the editor, only tiles and positions follow the scale. With lighting the
+/// tiles are darkened but the markers drawn over them are not. Fails if the
+/// level is too big for an image at the scale.
+pub fn render_level(
+    level: &Level,
+    assets: &Assets,
+    options: &RenderOptions,
+) -> Result<Image, ImageSizeError> {
+    let scale = options.scale.max(1);
+    let render_size = TILE_SIZE * scale;
+    let x_tiles = level.tiles.first().map_or(0, |row| row.len() as u32);
+    let y_tiles = level.tiles.len() as u32;
+    let width = x_tiles as u64 * render_size as u64;
+    let height = y_tiles as u64 * render_size as u64;
+    let mut image = u32::try_from(width)
+        .ok()
+        .zip(u32::try_from(height).ok())
+        .and_then(|(width, height)| Image::new(width, height))
+        .ok_or(ImageSizeError { width, height })?;
+
+    for (y, row) in level.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            let sheet = match tile.texture_type {
+                TextureType::FLOOR => &assets.floor,
+                TextureType::WALLS => &assets.walls,
+                TextureType::SHADOW => unreachable!(),
+            };
+            let position = (x as u32 * render_size, y as u32 * render_size);
+            draw_tile(&mut image, sheet, tile.id, position, scale);
+            if tile.shadow > 0 {
+                draw_tile(
+                    &mut image,
+                    &assets.shadows,
+                    tile.shadow - 1,
+                    position,
+                    scale,
+                );
+            }
+        }
+    }
+    if options.lighting {
+        let light_map = level.get_light_map(1);
+        for y in 0..image.height {
+            for x in 0..image.width {
+                let light = light_map.get(x / scale, y / scale);
+                let alpha = ((1.0 - light) * 255.0).round() as u8;
+                image.blend_pixel(x as i32, y as i32, [0, 0, 0, alpha]);
+            }
+        }
+    }
+
+    let to_image = |coordinates: &(u32, u32)| {
+        (
+            (coordinates.0 * scale) as i32,
+            (coordinates.1 * scale) as i32,
+        )
+    };
+    for (coordinates, spotlight) in &level.spotlights {
+        let (x, y) = to_image(coordinates);
+        image.draw_circle(x, y, get_spotlight_render_radius(spotlight), BLUE);
+    }
+    for (coordinates, steam) in &level.steams {
+        let (x, y) = to_image(coordinates);
+        let angle = (steam.angle as f32).to_radians();
+        for step in 0..6 {
+            let multiplier = step as f32 * 6.0 * steam.range as f32;
+            image.draw_circle(
+                x + (angle.sin() * multiplier) as i32,
+                y + (angle.cos() * multiplier) as i32,
+                get_steam_render_radius() + step * 2,
+                RED,
+            );
+        }
+    }
+
+    let crate_names = get_crates();
+    for (coordinates, crate_item) in &level.crates.staticc {
+        let (x, y) = to_image(coordinates);
+        let box_size = get_crate_render_size();
+        let color = match crate_item.crate_variant {
+            StaticCrate::Normal => LIGHT_GREEN,
+            StaticCrate::Deathmatch => LIGHT_BLUE,
+        };
+        image.draw_rect(x, y, box_size, box_size, color);
+        image.draw_rect(x + 1, y + 1, box_size - 2, box_size - 2, color);
+
+        let name = crate_names[crate_item.crate_class as usize][crate_item.crate_type as usize];
+        let (_, text_height) = fn2::get_text_size(&assets.font, name);
+        draw_text(
+            &mut image,
+            &assets.font,
+            x - 10,
+            y - 9 - (text_height + TEXT_SHADOW_PIXELS) as i32,
+            name,
+        );
+    }
+
+    for (text, position) in [("PL1", level.p1_position), ("PL2", level.p2_position)] {
+        draw_text(
+            &mut image,
+            &assets.font,
+            (position.0 * render_size) as i32,
+            (position.1 * render_size) as i32,
+            text,
+        );
+    }
+    Ok(image)
+}
+
+/// One pixel per tile, floors and walls in their own colors and shadowed
+/// tiles darker. Used for the editor minimap.
+pub fn render_minimap(level: &Level) -> Image {
+    let x_tiles = level.tiles.first().map_or(0, |row| row.len() as u32);
+    let mut image = Image::new(x_tiles, level.tiles.len() as u32).expect("level sizes are limited");
+    for (y, row) in level.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            let mut color = match tile.texture_type {
+                TextureType::WALLS => MINIMAP_WALL,
+                _ => MINIMAP_FLOOR,
+            };
+            if tile.shadow > 0 {
+                for channel in &mut color[..3] {
+                    *channel /= 2;
+                }
+            }
+            image.put_pixel(x as i32, y as i32, color);
+        }
+    }
+    image
+}
+
+fn draw_tile(image: &mut Image, sheet: &Image, id: u32, position: (u32, u32), scale: u32) {
+    let (sheet_x, sheet_y) = get_tile_coordinates(id, sheet.width, TILE_SIZE);
+    // Ids past the end of the sheet draw nothing, like an SDL copy outside the texture
+    if sheet_y
+        .checked_add(TILE_SIZE)
+        .is_none_or(|end| end > sheet.height)
+    {
+        return;
+    }
+    for y in 0..TILE_SIZE * scale {
+        for x in 0..TILE_SIZE * scale {
+            let color = sheet.get_pixel(sheet_x + x / scale, sheet_y + y / scale);
+            image.blend_pixel((position.0 + x) as i32, (position.1 + y) as i32, color);
+        }
+    }
+}
+
+/// Red text with a black drop shadow, like the editor's text textures.
+pub fn draw_text(image: &mut Image, font: &FN2, x: i32, y: i32, text: &str) {
+    let lines = fn2::get_text_lines(font, text);
+    for (offset, color) in [(TEXT_SHADOW_PIXELS, BLACK), (0, RED)] {
+        for &(line_x, line_y, width) in &lines {
+            image.fill_rect(
+                x + ((line_x + offset) * TEXT_SIZE_MULTIPLIER) as i32,
+                y + ((line_y + offset) * TEXT_SIZE_MULTIPLIER) as i32,
+                width * TEXT_SIZE_MULTIPLIER,
+                TEXT_SIZE_MULTIPLIER,
+                color,
+            );
+        }
+    }
+}
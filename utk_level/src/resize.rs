@@ -0,0 +1,185 @@
+//! Growing and cropping levels on any edge.
+
+use crate::level::{Level, Position, MAX_LEVEL_SIZE, TILE_SIZE};
+use crate::types::Tile;
+use std::collections::BTreeMap;
+
+/// Smallest level the game can show without scrolling past its edges.
+pub const MIN_LEVEL_SIZE: (u32, u32) = (16, 12);
+
+/// Tiles added (positive) or cropped (negative) on each edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Resize {
+    pub left: i32,
+    pub right: i32,
+    pub top: i32,
+    pub bottom: i32,
+}
+
+/// What did not fit into the resized level. Objects outside of it are
+/// dropped, player starts are moved to the nearest tile inside.
+#[derive(Debug, Default, PartialEq)]
+pub struct ResizeReport {
+    pub spotlights: usize,
+    pub steams: usize,
+    pub crates: usize,
+    pub p1_moved: bool,
+    pub p2_moved: bool,
+}
+
+impl ResizeReport {
+    pub fn is_empty(&self) -> bool {
+        *self == ResizeReport::default()
+    }
+}
+
+impl std::fmt::Display for ResizeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        for (amount, name) in [
+            (self.spotlights, "spotlights"),
+            (self.steams, "steams"),
+            (self.crates, "crates"),
+        ] {
+            if amount > 0 {
+                parts.push(format!("dropped {} {}", amount, name));
+            }
+        }
+        for (moved, name) in [(self.p1_moved, "PL1"), (self.p2_moved, "PL2")] {
+            if moved {
+                parts.push(format!("moved {} start", name));
+            }
+        }
+        if parts.is_empty() {
+            write!(f, "nothing lost")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ResizeError {
+    InvalidLevelSize(i64, i64),
+}
+
+impl std::fmt::Display for ResizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ResizeError::InvalidLevelSize(x, y) => write!(
+                f,
+                "invalid level size {}x{}, must be {}x{}-{}x{}",
+                x, y, MIN_LEVEL_SIZE.0, MIN_LEVEL_SIZE.1, MAX_LEVEL_SIZE, MAX_LEVEL_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResizeError {}
+
+fn shift_coordinate(value: u32, shift: i32, limit: u32) -> Option<u32> {
+    let value = value as i64 + shift as i64;
+    (0..limit as i64).contains(&value).then_some(value as u32)
+}
+
+fn shift_objects<T>(
+    objects: &mut BTreeMap<Position, T>,
+    shift: (i32, i32),
+    size: (u32, u32),
+) -> usize {
+    let amount = objects.len();
+    *objects = std::mem::take(objects)
+        .into_iter()
+        .filter_map(|(coordinates, object)| {
+            Some((
+                (
+                    shift_coordinate(coordinates.0, shift.0, size.0)?,
+                    shift_coordinate(coordinates.1, shift.1, size.1)?,
+                ),
+                object,
+            ))
+        })
+        .collect();
+    amount - objects.len()
+}
+
+// Returns the shifted position and whether it had to be moved inside
+fn shift_player(position: &Position, resize: &Resize, size: (u32, u32)) -> (Position, bool) {
+    let x = (position.0 as i64 + resize.left as i64).clamp(0, size.0 as i64 - 1);
+    let y = (position.1 as i64 + resize.top as i64).clamp(0, size.1 as i64 - 1);
+    let moved =
+        x != position.0 as i64 + resize.left as i64 || y != position.1 as i64 + resize.top as i64;
+    ((x as u32, y as u32), moved)
+}
+
+impl Level {
+    fn get_resized_size(&self, resize: &Resize) -> Result<(u32, u32), ResizeError> {
+        let x = self.tiles[0].len() as i64 + resize.left as i64 + resize.right as i64;
+        let y = self.tiles.len() as i64 + resize.top as i64 + resize.bottom as i64;
+        let is_valid = |value: i64, min: u32| (min as i64..=MAX_LEVEL_SIZE as i64).contains(&value);
+        if !is_valid(x, MIN_LEVEL_SIZE.0) || !is_valid(y, MIN_LEVEL_SIZE.1) {
+            return Err(ResizeError::InvalidLevelSize(x, y));
+        }
+        Ok((x as u32, y as u32))
+    }
+
+    /// Tells what [`Level::resize`] would lose without changing the level.
+    pub fn get_resize_report(&self, resize: &Resize) -> Result<ResizeReport, ResizeError> {
+        let (x_size, y_size) = self.get_resized_size(resize)?;
+        let pixel_size = (x_size * TILE_SIZE, y_size * TILE_SIZE);
+        let shift = (
+            resize.left * TILE_SIZE as i32,
+            resize.top * TILE_SIZE as i32,
+        );
+        let count_dropped = |positions: &mut dyn Iterator<Item = &Position>| {
+            positions
+                .filter(|coordinates| {
+                    shift_coordinate(coordinates.0, shift.0, pixel_size.0).is_none()
+                        || shift_coordinate(coordinates.1, shift.1, pixel_size.1).is_none()
+                })
+                .count()
+        };
+        Ok(ResizeReport {
+            spotlights: count_dropped(&mut self.spotlights.keys()),
+            steams: count_dropped(&mut self.steams.keys()),
+            crates: count_dropped(&mut self.crates.staticc.keys()),
+            p1_moved: shift_player(&self.p1_position, resize, (x_size, y_size)).1,
+            p2_moved: shift_player(&self.p2_position, resize, (x_size, y_size)).1,
+        })
+    }
+
+    /// Adds rows and columns of `fill` or crops them on each edge, keeping
+    /// the rest of the level and its objects where they were.
+    pub fn resize(&mut self, resize: &Resize, fill: &Tile) -> Result<ResizeReport, ResizeError> {
+        let (x_size, y_size) = self.get_resized_size(resize)?;
+        let mut tiles = vec![vec![*fill; x_size as usize]; y_size as usize];
+        for (y, row) in self.tiles.iter().enumerate() {
+            let Some(new_y) = shift_coordinate(y as u32, resize.top, y_size) else {
+                continue;
+            };
+            for (x, tile) in row.iter().enumerate() {
+                if let Some(new_x) = shift_coordinate(x as u32, resize.left, x_size) {
+                    tiles[new_y as usize][new_x as usize] = *tile;
+                }
+            }
+        }
+        self.tiles = tiles;
+
+        let pixel_size = (x_size * TILE_SIZE, y_size * TILE_SIZE);
+        let shift = (
+            resize.left * TILE_SIZE as i32,
+            resize.top * TILE_SIZE as i32,
+        );
+        let (p1_position, p1_moved) = shift_player(&self.p1_position, resize, (x_size, y_size));
+        let (p2_position, p2_moved) = shift_player(&self.p2_position, resize, (x_size, y_size));
+        self.p1_position = p1_position;
+        self.p2_position = p2_position;
+        Ok(ResizeReport {
+            spotlights: shift_objects(&mut self.spotlights, shift, pixel_size),
+            steams: shift_objects(&mut self.steams, shift, pixel_size),
+            crates: shift_objects(&mut self.crates.staticc, shift, pixel_size),
+            p1_moved,
+            p2_moved,
+        })
+    }
+}
@@ -0,0 +1,135 @@
+//! Lines, rectangle outlines and ellipse outlines drawn with tiles.
+
+use crate::area::Area;
+use crate::level::{Level, Position};
+use crate::types::TextureType;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Line,
+    Rectangle,
+    Ellipse,
+}
+
+// Bresenham's line, one tile per step along the longer axis
+fn get_line_tiles(start: (i64, i64), end: (i64, i64)) -> Vec<(i64, i64)> {
+    let (dx, dy) = ((end.0 - start.0).abs(), -(end.1 - start.1).abs());
+    let step = ((end.0 - start.0).signum(), (end.1 - start.1).signum());
+    let mut error = dx + dy;
+    let (mut x, mut y) = start;
+    let mut tiles = vec![(x, y)];
+    while (x, y) != end {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step.0;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step.1;
+        }
+        tiles.push((x, y));
+    }
+    tiles
+}
+
+fn get_rectangle_tiles(area: &Area) -> Vec<(i64, i64)> {
+    let (x0, y0) = (area.x as i64, area.y as i64);
+    let (x1, y1) = (x0 + area.width as i64 - 1, y0 + area.height as i64 - 1);
+    let mut tiles = Vec::new();
+    for x in x0..=x1 {
+        tiles.push((x, y0));
+        tiles.push((x, y1));
+    }
+    for y in y0 + 1..y1 {
+        tiles.push((x0, y));
+        tiles.push((x1, y));
+    }
+    tiles
+}
+
+// Midpoint ellipse fitted in the area, works for even sizes too. From
+// "A Rasterizing Algorithm for Drawing Curves" by Alois Zingl.
+fn get_ellipse_tiles(area: &Area) -> Vec<(i64, i64)> {
+    let (a, b) = (area.width as i64 - 1, area.height as i64 - 1);
+    let (mut x0, mut x1) = (area.x as i64, area.x as i64 + a);
+    let odd_height = b & 1;
+    let mut dx = 4 * (1 - a) * b * b;
+    let mut dy = 4 * (odd_height + 1) * a * a;
+    let mut error = dx + dy + odd_height * a * a;
+    let mut y0 = area.y as i64 + (b + 1) / 2;
+    let mut y1 = y0 - odd_height;
+    let (a_step, b_step) = (8 * a * a, 8 * b * b);
+
+    let mut tiles = Vec::new();
+    loop {
+        tiles.extend([(x1, y0), (x0, y0), (x0, y1), (x1, y1)]);
+        let doubled = 2 * error;
+        if doubled <= dy {
+            y0 += 1;
+            y1 -= 1;
+            dy += a_step;
+            error += dy;
+        }
+        if doubled >= dx || 2 * error > dy {
+            x0 += 1;
+            x1 -= 1;
+            dx += b_step;
+            error += dx;
+        }
+        if x0 > x1 {
+            break;
+        }
+    }
+    // Flat ellipses stop before reaching their tips
+    while y0 - y1 <= b {
+        tiles.extend([(x0 - 1, y0), (x1 + 1, y0), (x0 - 1, y1), (x1 + 1, y1)]);
+        y0 += 1;
+        y1 -= 1;
+    }
+    tiles
+}
+
+impl Shape {
+    /// Tiles of the shape dragged from `start` to `end`, in tile
+    /// coordinates. Rectangles and ellipses fill the box with these corners.
+    pub fn get_tiles(&self, start: &Position, end: &Position) -> Vec<Position> {
+        let area = Area::from_corners(start, end);
+        let tiles = match self {
+            Shape::Line => get_line_tiles(
+                (start.0 as i64, start.1 as i64),
+                (end.0 as i64, end.1 as i64),
+            ),
+            Shape::Rectangle => get_rectangle_tiles(&area),
+            Shape::Ellipse => get_ellipse_tiles(&area),
+        };
+        // Outlines meet themselves at corners and tips
+        let tiles: BTreeSet<Position> = tiles
+            .into_iter()
+            .filter(|(x, y)| area.contains_tile(&(*x as u32, *y as u32)))
+            .map(|(x, y)| (x as u32, y as u32))
+            .collect();
+        tiles.into_iter().collect()
+    }
+}
+
+impl Level {
+    /// Puts the selected tile, or shadow, on the tiles of the shape and
+    /// returns the area it covers.
+    pub fn draw_shape(
+        &mut self,
+        shape: Shape,
+        start: &Position,
+        end: &Position,
+        selected_tile_id: u32,
+        selected_texture: &TextureType,
+    ) -> Area {
+        self.put_tiles(
+            &shape.get_tiles(start, end),
+            |_| selected_tile_id,
+            selected_texture,
+        );
+        self.limit_area(&Area::from_corners(start, end))
+    }
+}
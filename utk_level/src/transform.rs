@@ -0,0 +1,226 @@
+//! Flipping and rotating levels and level areas.
+
+use crate::area::{Area, Clipboard};
+use crate::level::{Level, Position, TILE_SIZE};
+use crate::resize::{ResizeError, MIN_LEVEL_SIZE};
+use crate::types::{TextureType, Tile};
+use crate::util::get_crate_render_size;
+use std::collections::BTreeMap;
+
+// Wall pieces of WALLS1.PNG that are each other's mirror images. Only the
+// grey block and the red brick block (same layout, 64 ids later) have edges
+// that must line up, other walls keep their ids.
+const HORIZONTAL_MIRRORS: [(u32, u32); 4] = [(0, 2), (3, 34), (18, 32), (48, 49)];
+const VERTICAL_MIRRORS: [(u32, u32); 4] = [(0, 32), (2, 18), (17, 33), (50, 51)];
+// Mirrored over the top left to bottom right diagonal
+const DIAGONAL_MIRRORS: [(u32, u32); 6] = [(1, 16), (2, 32), (3, 17), (33, 34), (48, 51), (49, 50)];
+const MIRRORED_BLOCK_OFFSETS: [u32; 2] = [0, 64];
+
+fn mirror_wall(id: u32, mirrors: &[(u32, u32)]) -> u32 {
+    for offset in MIRRORED_BLOCK_OFFSETS {
+        for &(first, second) in mirrors {
+            if id == first + offset {
+                return second + offset;
+            }
+            if id == second + offset {
+                return first + offset;
+            }
+        }
+    }
+    id
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    FlipHorizontal,
+    FlipVertical,
+    RotateClockwise,
+    RotateCounterClockwise,
+}
+
+impl Transform {
+    /// Size of a `size` area after the transform.
+    pub fn get_size(&self, size: (u32, u32)) -> (u32, u32) {
+        match self {
+            Transform::FlipHorizontal | Transform::FlipVertical => size,
+            Transform::RotateClockwise | Transform::RotateCounterClockwise => (size.1, size.0),
+        }
+    }
+
+    fn apply(&self, position: (i64, i64), size: (u32, u32)) -> (i64, i64) {
+        let (x, y) = position;
+        let (width, height) = (size.0 as i64, size.1 as i64);
+        match self {
+            Transform::FlipHorizontal => (width - 1 - x, y),
+            Transform::FlipVertical => (x, height - 1 - y),
+            Transform::RotateClockwise => (height - 1 - y, x),
+            Transform::RotateCounterClockwise => (y, width - 1 - x),
+        }
+    }
+
+    // Moves a square of `box_size` pixels at `position` inside a `size`
+    // area, keeping it inside the transformed area
+    fn apply_box(&self, position: &Position, box_size: u32, size: (u32, u32)) -> Position {
+        let first = (position.0 as i64, position.1 as i64);
+        let last = (first.0 + box_size as i64 - 1, first.1 + box_size as i64 - 1);
+        let (first, last) = (self.apply(first, size), self.apply(last, size));
+        let new_size = self.get_size(size);
+        (
+            first.0.min(last.0).clamp(0, new_size.0 as i64 - 1) as u32,
+            first.1.min(last.1).clamp(0, new_size.1 as i64 - 1) as u32,
+        )
+    }
+
+    /// Steam direction after the transform. 0 is downwards and angles grow
+    /// counter clockwise.
+    pub fn apply_angle(&self, angle: u16) -> u16 {
+        match self {
+            Transform::FlipHorizontal => (360 - angle) % 360,
+            Transform::FlipVertical => (540 - angle) % 360,
+            Transform::RotateClockwise => (angle + 270) % 360,
+            Transform::RotateCounterClockwise => (angle + 90) % 360,
+        }
+    }
+
+    /// Swaps wall pieces so that their edges still face the same neighbours.
+    pub fn apply_tile(&self, tile: &Tile) -> Tile {
+        if tile.texture_type != TextureType::WALLS {
+            return *tile;
+        }
+        let id = match self {
+            Transform::FlipHorizontal => mirror_wall(tile.id, &HORIZONTAL_MIRRORS),
+            Transform::FlipVertical => mirror_wall(tile.id, &VERTICAL_MIRRORS),
+            Transform::RotateClockwise => {
+                mirror_wall(mirror_wall(tile.id, &DIAGONAL_MIRRORS), &HORIZONTAL_MIRRORS)
+            }
+            Transform::RotateCounterClockwise => {
+                mirror_wall(mirror_wall(tile.id, &DIAGONAL_MIRRORS), &VERTICAL_MIRRORS)
+            }
+        };
+        Tile { id, ..*tile }
+    }
+}
+
+fn transform_objects<T: Copy>(
+    objects: &BTreeMap<Position, T>,
+    transform: Transform,
+    box_size: u32,
+    pixel_size: (u32, u32),
+    transform_object: impl Fn(&T) -> T,
+) -> BTreeMap<Position, T> {
+    objects
+        .iter()
+        .map(|(coordinates, object)| {
+            (
+                transform.apply_box(coordinates, box_size, pixel_size),
+                transform_object(object),
+            )
+        })
+        .collect()
+}
+
+impl Clipboard {
+    pub fn transform(&self, transform: Transform) -> Clipboard {
+        let size = self.get_size();
+        let (width, height) = transform.get_size(size);
+        let mut tiles = Vec::with_capacity(height as usize);
+        for y in 0..height as i64 {
+            let row = (0..width as i64)
+                .map(|x| {
+                    // Source tile is found with the inverse transform
+                    let (source_x, source_y) = match transform {
+                        Transform::RotateClockwise => {
+                            Transform::RotateCounterClockwise.apply((x, y), (width, height))
+                        }
+                        Transform::RotateCounterClockwise => {
+                            Transform::RotateClockwise.apply((x, y), (width, height))
+                        }
+                        flip => flip.apply((x, y), size),
+                    };
+                    transform.apply_tile(&self.tiles[source_y as usize][source_x as usize])
+                })
+                .collect();
+            tiles.push(row);
+        }
+
+        let pixel_size = (size.0 * TILE_SIZE, size.1 * TILE_SIZE);
+        Clipboard {
+            tiles,
+            spotlights: transform_objects(&self.spotlights, transform, 1, pixel_size, |s| *s),
+            steams: transform_objects(&self.steams, transform, 1, pixel_size, |steam| {
+                let mut steam = *steam;
+                steam.angle = transform.apply_angle(steam.angle);
+                steam
+            }),
+            crates: transform_objects(
+                &self.crates,
+                transform,
+                get_crate_render_size(),
+                pixel_size,
+                |crate_item| *crate_item,
+            ),
+        }
+    }
+}
+
+impl Level {
+    fn get_size(&self) -> (u32, u32) {
+        (self.tiles[0].len() as u32, self.tiles.len() as u32)
+    }
+
+    // Player starts inside `area` follow the transformed area, which keeps
+    // its top left corner
+    fn transform_players(&mut self, area: &Area, transform: Transform) {
+        let (x_size, y_size) = self.get_size();
+        for position in [&mut self.p1_position, &mut self.p2_position] {
+            if !area.contains_tile(position) {
+                continue;
+            }
+            let relative = (position.0 - area.x, position.1 - area.y);
+            let moved = transform.apply_box(&relative, 1, (area.width, area.height));
+            *position = (
+                (area.x + moved.0).min(x_size - 1),
+                (area.y + moved.1).min(y_size - 1),
+            );
+        }
+    }
+
+    /// Flips or rotates the whole level. Rotating swaps the level size and
+    /// fails if the result would be too narrow.
+    pub fn transform(&mut self, transform: Transform) -> Result<(), ResizeError> {
+        let (x_size, y_size) = transform.get_size(self.get_size());
+        if x_size < MIN_LEVEL_SIZE.0 || y_size < MIN_LEVEL_SIZE.1 {
+            return Err(ResizeError::InvalidLevelSize(x_size as i64, y_size as i64));
+        }
+        let area = Area {
+            x: 0,
+            y: 0,
+            width: self.get_size().0,
+            height: self.get_size().1,
+        };
+        let clipboard = self.copy_area(&area).transform(transform);
+        self.tiles = clipboard.tiles;
+        self.spotlights = clipboard.spotlights;
+        self.steams = clipboard.steams;
+        self.crates.staticc = clipboard.crates;
+        self.transform_players(&area, transform);
+        Ok(())
+    }
+
+    /// Flips or rotates an area in place and returns the area it covers
+    /// afterwards. A rotated area keeps its top left corner, whatever it
+    /// leaves uncovered is filled with `fill` and whatever falls outside of
+    /// the level is left out.
+    pub fn transform_area(&mut self, area: &Area, transform: Transform, fill: &Tile) -> Area {
+        let area = self.limit_area(area);
+        let clipboard = self.cut_area(&area, fill).transform(transform);
+        self.paste(&clipboard, &(area.x, area.y));
+        self.transform_players(&area, transform);
+        let (width, height) = clipboard.get_size();
+        self.limit_area(&Area {
+            width,
+            height,
+            ..area
+        })
+    }
+}
@@ -0,0 +1,27 @@
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureType {
+    FLOOR = 0,
+    WALLS = 1,
+    SHADOW = 2,
+}
+
+impl TextureType {
+    pub fn from_u32(value: u32) -> Option<TextureType> {
+        match value {
+            0 => Some(TextureType::FLOOR),
+            1 => Some(TextureType::WALLS),
+            2 => Some(TextureType::SHADOW),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tile {
+    pub texture_type: TextureType,
+    pub id: u32,
+    pub shadow: u32,
+}
+
+pub type Tiles = Vec<Vec<Tile>>;
@@ -0,0 +1,39 @@
+pub fn get_distance_between_points(p0: &(u32, u32), p1: &(u32, u32)) -> f64 {
+    let x0 = p0.0 as i32;
+    let x1 = p1.0 as i32;
+    let y0 = p0.1 as i32;
+    let y1 = p1.1 as i32;
+    (((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)) as f64).sqrt()
+}
+
+/// Top left corner of tile `id` in a sheet `width` pixels wide. Ids far
+/// past the end of any sheet give `u32::MAX` as y instead of overflowing.
+pub fn get_tile_coordinates(id: u32, width: u32, tile_size: u32) -> (u32, u32) {
+    let offset = id as u64 * tile_size as u64;
+    let x = offset % width as u64;
+    let y = offset / width as u64 * tile_size as u64;
+    (x as u32, u32::try_from(y).unwrap_or(u32::MAX))
+}
+
+pub fn get_spotlight_render_radius(spotlight: &u8) -> u32 {
+    *spotlight as u32 * 5 + 5
+}
+
+pub fn get_steam_render_radius() -> u32 {
+    5
+}
+
+pub fn get_crate_render_size() -> u32 {
+    28
+}
+
+pub fn check_box_click(
+    point_position: &(u32, u32),
+    box_position: &(u32, u32),
+    box_size: u32,
+) -> bool {
+    point_position.0 >= box_position.0
+        && point_position.0 < box_position.0 + box_size
+        && point_position.1 >= box_position.1
+        && point_position.1 < box_position.1 + box_size
+}
@@ -0,0 +1,102 @@
+//! Brush that paints a random tile out of a weighted set, so that floors
+//! made of near identical variants do not look tiled.
+
+use crate::area::Area;
+use crate::level::{Level, Position};
+use crate::random::Random;
+use crate::types::TextureType;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariationBrush {
+    pub texture_type: TextureType,
+    // (tile id, weight)
+    weights: Vec<(u32, u32)>,
+    /// Same seed gives the same tile on the same level tile.
+    pub seed: u64,
+}
+
+impl VariationBrush {
+    pub fn new(texture_type: TextureType, seed: u64) -> VariationBrush {
+        VariationBrush {
+            texture_type,
+            weights: Vec::new(),
+            seed,
+        }
+    }
+
+    /// Adds the tile to the set, or makes it more common if it is there
+    /// already.
+    pub fn add(&mut self, id: u32) {
+        match self.weights.iter_mut().find(|(tile_id, _)| *tile_id == id) {
+            Some((_, weight)) => *weight += 1,
+            None => self.weights.push((id, 1)),
+        }
+    }
+
+    /// 0 if the tile is not in the set.
+    pub fn get_weight(&self, id: u32) -> u32 {
+        self.weights
+            .iter()
+            .find(|(tile_id, _)| *tile_id == id)
+            .map_or(0, |(_, weight)| *weight)
+    }
+
+    /// Tile ids of the set with their weights.
+    pub fn get_weights(&self) -> &[(u32, u32)] {
+        &self.weights
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Tile to paint on `position`. Depends only on the set, the seed and
+    /// the position, not on the order tiles are painted in.
+    pub fn get_id(&self, position: &Position) -> u32 {
+        let total: u32 = self.weights.iter().map(|(_, weight)| weight).sum();
+        assert!(total > 0, "no tiles in the brush");
+        // Seed is mixed first so that nearby seeds do not give shifted copies
+        let key = ((position.0 as u64) << 32) | position.1 as u64;
+        let mut random = Random::new(Random::new(self.seed).next_u64() ^ key);
+        let mut pick = random.range(0, total - 1);
+        for (id, weight) in &self.weights {
+            if pick < *weight {
+                return *id;
+            }
+            pick -= weight;
+        }
+        unreachable!()
+    }
+}
+
+impl Level {
+    /// Paints a random tile from the brush on each of the `tiles` inside the
+    /// level. Returns the area of the tiles that changed.
+    pub fn put_random_tiles(&mut self, tiles: &[Position], brush: &VariationBrush) -> Option<Area> {
+        self.put_tiles(
+            tiles,
+            |position| brush.get_id(position),
+            &brush.texture_type,
+        )
+    }
+
+    /// Picks again every tile of the brush set inside `area`, use after
+    /// changing the seed. Returns the area of the tiles that changed.
+    pub fn randomize_area(&mut self, area: &Area, brush: &VariationBrush) -> Option<Area> {
+        let area = self.limit_area(area);
+        let tiles: Vec<Position> = area
+            .get_tiles()
+            .into_iter()
+            .filter(|&(x, y)| {
+                let tile = &self.tiles[y as usize][x as usize];
+                match brush.texture_type {
+                    TextureType::SHADOW => tile.shadow > 0 && brush.get_weight(tile.shadow - 1) > 0,
+                    texture_type => {
+                        tile.texture_type == texture_type && brush.get_weight(tile.id) > 0
+                    }
+                }
+            })
+            .collect();
+        self.put_random_tiles(&tiles, brush)
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use utk_level::area::Area;
+use utk_level::crates::CrateClass;
+use utk_level::level::{StaticCrate, StaticCrateType, Steam};
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+const FLOOR: Tile = Tile {
+    texture_type: TextureType::FLOOR,
+    id: 0,
+    shadow: 0,
+};
+
+fn get_level() -> Level {
+    let mut level = Level::get_default_level((16, 12));
+    level.tiles[2][3] = Tile {
+        texture_type: TextureType::WALLS,
+        id: 7,
+        shadow: 0,
+    };
+    level.tiles[3][4].shadow = 2;
+    level.put_spotlight_to_level(&(65, 45), 4);
+    level.put_steam_to_level(
+        &(99, 79),
+        &Steam {
+            range: 2,
+            angle: 45,
+        },
+    );
+    level.put_crate_to_level(
+        &(80, 40),
+        &StaticCrateType {
+            crate_variant: StaticCrate::Normal,
+            crate_class: CrateClass::Energy,
+            crate_type: 0,
+        },
+    );
+    // Outside of the copied area
+    level.put_spotlight_to_level(&(100, 40), 1);
+    level
+}
+
+#[test]
+fn areas_are_built_from_any_corners() {
+    let area = Area::from_corners(&(5, 2), &(3, 4));
+    assert_eq!(
+        area,
+        Area {
+            x: 3,
+            y: 2,
+            width: 3,
+            height: 3
+        }
+    );
+    assert!(area.contains_pixel(&(60, 40)));
+    assert!(area.contains_pixel(&(119, 99)));
+    assert!(!area.contains_pixel(&(120, 40)));
+}
+
+#[test]
+fn copy_translates_objects_into_the_area() {
+    let level = get_level();
+    let clipboard = level.copy_area(&Area::from_corners(&(3, 2), &(4, 3)));
+
+    assert_eq!(clipboard.get_size(), (2, 2));
+    assert_eq!(clipboard.tiles[0][0].id, 7);
+    assert_eq!(clipboard.tiles[1][1].shadow, 2);
+    assert_eq!(clipboard.spotlights, BTreeMap::from([((5, 5), 4)]));
+    assert_eq!(clipboard.steams.keys().collect::<Vec<_>>(), [&(39, 39)]);
+    assert_eq!(clipboard.crates.keys().collect::<Vec<_>>(), [&(20, 0)]);
+}
+
+#[test]
+fn cut_and_paste_moves_area_and_objects() {
+    let mut level = get_level();
+    let area = Area::from_corners(&(3, 2), &(4, 3));
+    let clipboard = level.cut_area(&area, &FLOOR);
+
+    assert_eq!(level.tiles[2][3], FLOOR);
+    assert_eq!(level.tiles[3][4], FLOOR);
+    assert_eq!(level.spotlights, BTreeMap::from([((100, 40), 1)]));
+    assert!(level.steams.is_empty());
+    assert!(level.crates.staticc.is_empty());
+
+    level.paste(&clipboard, &(10, 6));
+    assert_eq!(level.tiles[6][10].id, 7);
+    assert_eq!(level.tiles[7][11].shadow, 2);
+    assert_eq!(level.spotlights.get(&(205, 125)), Some(&4));
+    assert!(level.steams.contains_key(&(239, 159)));
+    assert!(level.crates.staticc.contains_key(&(220, 120)));
+}
+
+#[test]
+fn paste_replaces_objects_and_drops_what_falls_outside() {
+    let mut level = get_level();
+    let clipboard = level.copy_area(&Area::from_corners(&(3, 2), &(4, 3)));
+
+    // Only the left column fits, objects on the right column are dropped
+    level.paste(&clipboard, &(15, 0));
+    assert_eq!(level.tiles[0][15].id, 7);
+    assert_eq!(level.tiles[0].len(), 16);
+    assert!(level.spotlights.contains_key(&(305, 5)));
+    assert!(!level.crates.staticc.contains_key(&(320, 0)));
+
+    // Pasting an empty area over objects removes them
+    let empty = level.copy_area(&Area::from_corners(&(8, 8), &(9, 9)));
+    level.paste(&empty, &(3, 2));
+    assert!(!level.spotlights.contains_key(&(65, 45)));
+    assert!(level.spotlights.contains_key(&(100, 40)));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::rules;
+use utk_level::area::Area;
+use utk_level::autotile::{AutoTileRules, RulesError};
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+fn get_wall(id: u32) -> Tile {
+    Tile {
+        texture_type: TextureType::WALLS,
+        id,
+        shadow: 0,
+    }
+}
+
+#[test]
+fn default_level_border_matches_rules() {
+    let level = Level::get_default_level((20, 14));
+    let mut tiled = level.clone();
+    // Start from plain walls so that every piece is picked by the rules
+    for row in &mut tiled.tiles {
+        for tile in row.iter_mut() {
+            if tile.texture_type == TextureType::WALLS {
+                tile.id = 4;
+            }
+        }
+    }
+    tiled.auto_tile(&rules());
+    assert_eq!(tiled.tiles, level.tiles);
+}
+
+#[test]
+fn neighbours_are_retiled() {
+    let rules = rules();
+    let mut level = Level::get_default_level((20, 14));
+    level.tiles[5][5] = get_wall(68);
+    level.auto_tile_area(&rules, &Area::from_corners(&(5, 5), &(5, 5)));
+    assert_eq!(level.tiles[5][5], get_wall(83));
+
+    level.tiles[5][6] = get_wall(68);
+    level.auto_tile_area(&rules, &Area::from_corners(&(6, 5), &(6, 5)));
+    assert_eq!(level.tiles[5][5], get_wall(112));
+    assert_eq!(level.tiles[5][6], get_wall(113));
+
+    // Walls outside of any rule block are left alone
+    level.tiles[4][5] = get_wall(7);
+    level.auto_tile_area(&rules, &Area::from_corners(&(5, 4), &(5, 4)));
+    assert_eq!(level.tiles[4][5], get_wall(7));
+    assert_eq!(level.tiles[5][5], get_wall(96));
+}
+
+#[test]
+fn incomplete_rules_are_refused() {
+    assert!(matches!(
+        AutoTileRules::parse("[walls]\n- 4\nTB 1\n"),
+        Err(RulesError::MissingRules(name)) if name == "walls"
+    ));
+    assert!(matches!(
+        AutoTileRules::parse("# comment\n\n[walls]\nTX 1\n"),
+        Err(RulesError::Syntax(4, _))
+    ));
+}
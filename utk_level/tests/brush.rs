@@ -0,0 +1,87 @@
+use utk_level::area::Area;
+use utk_level::brush::{Brush, BrushShape};
+use utk_level::level::Steam;
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+#[test]
+fn square_brush_is_centred() {
+    let brush = Brush {
+        size: 3,
+        shape: BrushShape::Square,
+    };
+    let tiles = brush.get_tiles(&(5, 5));
+    assert_eq!(tiles.len(), 9);
+    assert_eq!(
+        Area::from_tiles(tiles),
+        Some(Area {
+            x: 4,
+            y: 4,
+            width: 3,
+            height: 3,
+        })
+    );
+    // Cut at the level edge
+    assert_eq!(brush.get_tiles(&(0, 0)).len(), 4);
+    assert_eq!(Brush::default().get_tiles(&(2, 3)), vec![(2, 3)]);
+}
+
+#[test]
+fn round_brush_has_no_corners() {
+    let brush = Brush {
+        size: 5,
+        shape: BrushShape::Round,
+    };
+    let tiles = brush.get_tiles(&(5, 5));
+    assert_eq!(tiles.len(), 21);
+    assert!(!tiles.contains(&(3, 3)) && !tiles.contains(&(7, 7)));
+    assert!(tiles.contains(&(3, 5)) && tiles.contains(&(5, 7)));
+
+    let small = Brush {
+        size: 3,
+        shape: BrushShape::Round,
+    };
+    assert_eq!(small.get_tiles(&(5, 5)).len(), 5);
+}
+
+#[test]
+fn eraser_clears_shadows_and_objects() {
+    let mut level = Level::get_default_level((16, 12));
+    level.tiles[3][3] = Tile {
+        texture_type: TextureType::FLOOR,
+        id: 7,
+        shadow: 4,
+    };
+    level.put_spotlight_to_level(&(65, 65), 3);
+    level.put_steam_to_level(&(200, 200), &Steam { angle: 0, range: 2 });
+    let fill = Tile {
+        texture_type: TextureType::FLOOR,
+        id: 2,
+        shadow: 0,
+    };
+    let tiles = Brush {
+        size: 3,
+        shape: BrushShape::Square,
+    }
+    .get_tiles(&(3, 3));
+
+    let mut kept = level.clone();
+    assert!(kept.erase(&tiles, &fill, false).is_some());
+    assert_eq!(kept.tiles[3][3], fill);
+    assert_eq!(kept.spotlights.len(), 1);
+
+    let area = level.erase(&tiles, &fill, true);
+    assert_eq!(
+        area,
+        Some(Area {
+            x: 2,
+            y: 2,
+            width: 3,
+            height: 3,
+        })
+    );
+    assert!(level.spotlights.is_empty());
+    assert_eq!(level.steams.len(), 1);
+    // Nothing left to erase
+    assert_eq!(level.erase(&tiles, &fill, true), None);
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use utk_level::Level;
+
+fn golden(version: u32) -> String {
+    format!("{}/tests/data/V{}.LEV", env!("CARGO_MANIFEST_DIR"), version)
+}
+
+fn output_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_utk-level"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn info_prints_summary() {
+    let output = run(&["info", &golden(5)]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("version: 5\n"));
+    assert!(stdout.contains("size: 16x12\n"));
+    assert!(stdout.contains("comment: v5 golden\n"));
+    assert!(stdout.contains("normal crates: 2\n"));
+    assert!(stdout.contains("deathmatch crates: 1\n"));
+}
+
+#[test]
+fn validate_fails_on_broken_level() {
+    let broken = output_path("BROKEN.LEV");
+    std::fs::write(&broken, &std::fs::read(golden(5)).unwrap()[..100]).unwrap();
+
+    assert!(run(&["validate", &golden(1), &golden(5)]).status.success());
+    let output = run(&["validate", &golden(5), broken.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("unexpected end of file in tiles"));
+}
+
+#[test]
+fn convert_writes_requested_version() {
+    let converted = output_path("CONVERTED.LEV");
+    let output = run(&[
+        "convert",
+        &golden(5),
+        converted.to_str().unwrap(),
+        "--to-version",
+        "4",
+    ]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("drops 3 static crates"));
+    let (level, version) =
+        Level::read_with_version_from(&std::fs::read(converted).unwrap()[..]).unwrap();
+    assert_eq!(version, 4);
+    assert!(level.crates.staticc.is_empty());
+    assert_eq!(level.spotlights.len(), 2);
+}
+
+#[test]
+fn set_edits_general_info_and_random_crates() {
+    let edited = output_path("EDITED.LEV");
+    let output = run(&[
+        "set",
+        &golden(3),
+        "--output",
+        edited.to_str().unwrap(),
+        "comment=scripted",
+        "time_limit=90",
+        "enemies.7=4",
+        "deathmatch.bullets=1,2,3,4,5,6,7,8,9",
+        "normal.energy=0",
+    ]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("version 3 drops 4 enemies of type 8"));
+
+    let (level, version) =
+        Level::read_with_version_from(&std::fs::read(&edited).unwrap()[..]).unwrap();
+    assert_eq!(version, 3);
+    assert_eq!(level.general_info.comment, "scripted");
+    assert_eq!(level.general_info.time_limit, 90);
+    assert_eq!(
+        level.crates.random.deathmatch.bullets,
+        [1, 2, 3, 4, 5, 6, 7, 8, 9]
+    );
+    assert_eq!(level.crates.random.normal.energy, 0);
+    // Version 3 cannot store the eighth enemy type
+    assert_eq!(level.general_info.enemy_table[7], 0);
+}
+
+#[test]
+fn set_rejects_unknown_keys() {
+    let output = run(&["set", &golden(5), "--output", "unused", "speed=3"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn render_writes_scaled_png() {
+    let rendered = output_path("RENDERED.PNG");
+    let output = run(&[
+        "render",
+        &golden(5),
+        rendered.to_str().unwrap(),
+        "--scale",
+        "2",
+        "--assets",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../assets"),
+    ]);
+    assert!(output.status.success());
+    let image = utk_level::image::Image::load_png(rendered.to_str().unwrap()).unwrap();
+    assert_eq!((image.width, image.height), (640, 480));
+}
+
+#[test]
+fn generate_is_repeatable_with_seed() {
+    let generate = |name: &str| {
+        let generated = output_path(name);
+        let output = run(&[
+            "generate",
+            generated.to_str().unwrap(),
+            "--seed",
+            "7",
+            "--size",
+            "40x30",
+            "--rooms",
+            "6",
+            "--crates",
+            "--assets",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../assets"),
+        ]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "seed: 7\n");
+        let (level, _) =
+            Level::read_with_version_from(&std::fs::read(&generated).unwrap()[..]).unwrap();
+        level
+    };
+    let level = generate("GENERATED1.LEV");
+    assert_eq!((level.tiles[0].len(), level.tiles.len()), (40, 30));
+    assert_eq!(generate("GENERATED2.LEV"), level);
+}
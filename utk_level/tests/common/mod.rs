@@ -0,0 +1,10 @@
+use utk_level::autotile::AutoTileRules;
+
+/// Wall piece rules shipped with the editor assets.
+pub fn rules() -> AutoTileRules {
+    AutoTileRules::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../assets/WALLS1_RULES.TXT"
+    ))
+    .unwrap()
+}
@@ -0,0 +1,91 @@
+use utk_level::area::Area;
+use utk_level::fill::{Connectivity, FillOptions};
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+fn get_tile(texture_type: TextureType, id: u32) -> Tile {
+    Tile {
+        texture_type,
+        id,
+        shadow: 0,
+    }
+}
+
+// Default level split in two by a wall column with a diagonal gap
+fn get_level() -> Level {
+    let mut level = Level::get_default_level((16, 12));
+    for y in 1..11 {
+        level.tiles[y][8] = get_tile(TextureType::WALLS, 4);
+    }
+    level.tiles[5][8] = get_tile(TextureType::FLOOR, 0);
+    level.tiles[4][8] = get_tile(TextureType::WALLS, 4);
+    level.tiles[5][7] = get_tile(TextureType::WALLS, 4);
+    level.tiles[5][9] = get_tile(TextureType::WALLS, 4);
+    // Different floor inside the left half
+    level.tiles[3][3] = get_tile(TextureType::FLOOR, 5);
+    level
+}
+
+fn count(level: &Level, texture_type: TextureType, id: u32) -> usize {
+    level
+        .tiles
+        .iter()
+        .flatten()
+        .filter(|tile| tile.texture_type == texture_type && tile.id == id)
+        .count()
+}
+
+#[test]
+fn fill_stops_at_different_tiles() {
+    let mut level = get_level();
+    let options = FillOptions::default();
+    let area = level.flood_fill(&(2, 2), 9, &TextureType::FLOOR, &options);
+    assert_eq!(
+        area,
+        Some(Area {
+            x: 1,
+            y: 1,
+            width: 7,
+            height: 10,
+        })
+    );
+    assert_eq!(level.tiles[3][3], get_tile(TextureType::FLOOR, 5));
+    assert_eq!(level.tiles[2][10], get_tile(TextureType::FLOOR, 0));
+    // Filling with the same tile changes nothing
+    assert_eq!(
+        level.flood_fill(&(2, 2), 9, &TextureType::FLOOR, &options),
+        None
+    );
+}
+
+#[test]
+fn eight_connectivity_passes_diagonal_gaps() {
+    let mut level = get_level();
+    let options = FillOptions {
+        connectivity: Connectivity::Eight,
+        floors_only: false,
+    };
+    level.flood_fill(&(2, 2), 9, &TextureType::FLOOR, &options);
+    assert_eq!(level.tiles[2][10], get_tile(TextureType::FLOOR, 9));
+}
+
+#[test]
+fn floors_only_fills_whole_room() {
+    let mut level = get_level();
+    let options = FillOptions {
+        connectivity: Connectivity::Four,
+        floors_only: true,
+    };
+    assert_eq!(
+        level.flood_fill(&(8, 1), 9, &TextureType::FLOOR, &options),
+        None
+    );
+    level.flood_fill(&(2, 2), 9, &TextureType::FLOOR, &options);
+    assert_eq!(level.tiles[3][3].id, 9);
+    assert_eq!(count(&level, TextureType::FLOOR, 9), 7 * 10 - 1);
+
+    // Shadows are filled the same way
+    level.flood_fill(&(2, 2), 1, &TextureType::SHADOW, &options);
+    assert_eq!(level.tiles[3][3].shadow, 2);
+    assert_eq!(level.tiles[3][10].shadow, 0);
+}
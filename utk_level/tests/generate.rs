@@ -0,0 +1,83 @@
+mod common;
+
+use common::rules;
+use utk_level::generate::{generate_level, GenerateError, GeneratorOptions};
+use utk_level::types::TextureType;
+use utk_level::Level;
+
+// Floor tiles reachable from `start` without crossing walls
+fn get_reachable(level: &Level, start: (u32, u32)) -> Vec<Vec<bool>> {
+    let mut reached = vec![vec![false; level.tiles[0].len()]; level.tiles.len()];
+    let mut stack = vec![(start.0 as usize, start.1 as usize)];
+    while let Some((x, y)) = stack.pop() {
+        if reached[y][x] || level.tiles[y][x].texture_type == TextureType::WALLS {
+            continue;
+        }
+        reached[y][x] = true;
+        stack.extend([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]);
+    }
+    reached
+}
+
+#[test]
+fn same_seed_gives_same_level() {
+    let rules = rules();
+    let options = GeneratorOptions {
+        spotlights: true,
+        crates: true,
+        ..GeneratorOptions::default()
+    };
+    let level = generate_level(42, &options, &rules).unwrap();
+    assert_eq!(generate_level(42, &options, &rules).unwrap(), level);
+    assert_ne!(generate_level(43, &options, &rules).unwrap(), level);
+    assert!(!level.spotlights.is_empty());
+}
+
+#[test]
+fn players_start_in_connected_rooms() {
+    let rules = rules();
+    for seed in 0..50 {
+        let options = GeneratorOptions {
+            size: (40, 30),
+            rooms: 8,
+            corridor_width: 1 + seed as u32 % 3,
+            wall_thickness: 1 + seed as u32 % 2,
+            ..GeneratorOptions::default()
+        };
+        let level = generate_level(seed, &options, &rules).unwrap();
+        assert_eq!((level.tiles[0].len(), level.tiles.len()), (40, 30));
+        assert_ne!(level.p1_position, level.p2_position);
+        let reached = get_reachable(&level, level.p1_position);
+        assert!(
+            reached[level.p2_position.1 as usize][level.p2_position.0 as usize],
+            "seed {}",
+            seed
+        );
+        // Level edge is always wall
+        assert!(level.tiles[0]
+            .iter()
+            .all(|tile| tile.texture_type == TextureType::WALLS));
+    }
+}
+
+#[test]
+fn invalid_options_are_refused() {
+    let rules = rules();
+    let options = GeneratorOptions {
+        corridor_width: 0,
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        generate_level(1, &options, &rules),
+        Err(GenerateError::InvalidOption("corridor width", 0))
+    );
+    let options = GeneratorOptions {
+        size: (16, 12),
+        wall_thickness: 4,
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        generate_level(1, &options, &rules),
+        Err(GenerateError::NotEnoughSpace)
+    );
+}
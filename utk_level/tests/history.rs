@@ -0,0 +1,111 @@
+use utk_level::history::History;
+use utk_level::level::{StaticCrate, StaticCrateType, Steam};
+use utk_level::types::TextureType;
+use utk_level::Level;
+
+fn edit(history: &mut History, level: &mut Level, action: impl FnOnce(&mut Level)) -> bool {
+    history.begin(level);
+    action(level);
+    history.commit(level)
+}
+
+#[test]
+fn every_part_of_the_level_is_undone_and_redone() {
+    let mut level = Level::get_default_level((16, 12));
+    let original = level.clone();
+    let mut history = History::new(10);
+
+    assert!(edit(&mut history, &mut level, |level| {
+        level.put_tile_to_level(17, Some(3), &TextureType::WALLS);
+        level.create_shadows();
+        level.p1_position = (4, 4);
+        level.put_spotlight_to_level(&(30, 30), 5);
+        level.put_steam_to_level(
+            &(60, 60),
+            &Steam {
+                range: 3,
+                angle: 90,
+            },
+        );
+        level.put_crate_to_level(
+            &(90, 90),
+            &StaticCrateType {
+                crate_variant: StaticCrate::Deathmatch,
+                crate_class: utk_level::crates::CrateClass::Bullet,
+                crate_type: 2,
+            },
+        );
+        level.general_info.comment = "undo me".to_string();
+        level.crates.random.normal.energy = 42;
+    }));
+    let edited = level.clone();
+
+    assert!(history.undo(&mut level));
+    assert_eq!(level, original);
+    assert!(!history.undo(&mut level));
+    assert!(history.redo(&mut level));
+    assert_eq!(level, edited);
+    assert!(!history.redo(&mut level));
+}
+
+#[test]
+fn transactions_group_changes_and_skip_no_ops() {
+    let mut level = Level::get_default_level((16, 12));
+    let mut history = History::new(10);
+
+    history.begin(&level);
+    for id in 20..30 {
+        level.put_tile_to_level(id, Some(1), &TextureType::FLOOR);
+    }
+    // Nested begin keeps the first snapshot
+    history.begin(&level);
+    level.put_spotlight_to_level(&(10, 10), 1);
+    level.put_spotlight_to_level(&(10, 10), 2);
+    assert!(history.commit(&level));
+
+    assert!(!edit(&mut history, &mut level, |level| level.scroll = (1, 1)));
+
+    history.undo(&mut level);
+    assert!(level.spotlights.is_empty());
+    assert_eq!(level.tiles[1][4].id, 0);
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn new_edit_discards_redo_and_depth_is_limited() {
+    let mut level = Level::get_default_level((16, 12));
+    let mut history = History::new(3);
+
+    for intensity in 0..5 {
+        edit(&mut history, &mut level, |level| {
+            level.put_spotlight_to_level(&(10, 10), intensity)
+        });
+    }
+    while history.undo(&mut level) {}
+    assert_eq!(level.spotlights.get(&(10, 10)), Some(&1));
+
+    history.redo(&mut level);
+    edit(&mut history, &mut level, |level| {
+        level.put_spotlight_to_level(&(10, 10), 9)
+    });
+    assert!(!history.can_redo());
+
+    let mut disabled = History::new(0);
+    assert!(!edit(&mut disabled, &mut level, |level| level
+        .p2_position =
+        (2, 2)));
+    assert!(!disabled.undo(&mut level));
+}
+
+#[test]
+fn resized_tiles_are_restored() {
+    let mut level = Level::get_default_level((16, 12));
+    let original = level.clone();
+    let mut history = History::new(10);
+
+    edit(&mut history, &mut level, |level| {
+        level.tiles = Level::get_default_level((20, 14)).tiles
+    });
+    history.undo(&mut level);
+    assert_eq!(level, original);
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use utk_level::crates::CrateClass;
+use utk_level::level::{
+    DeserializationError, FileTypeError, Section, SerializationError, StaticCrate, StaticCrateType,
+    Steam, VersionLoss, VERSION,
+};
+use utk_level::types::TextureType;
+use utk_level::Level;
+
+fn read_golden(version: u32) -> Vec<u8> {
+    std::fs::read(format!(
+        "{}/tests/data/V{}.LEV",
+        env!("CARGO_MANIFEST_DIR"),
+        version
+    ))
+    .unwrap()
+}
+
+fn load_golden(version: u32) -> Level {
+    Level::read_from(&read_golden(version)[..]).unwrap()
+}
+
+fn write(level: &Level, version: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    level.write_to_version(&mut data, version).unwrap();
+    data
+}
+
+fn get_content_error(data: &[u8]) -> (u64, Section, FileTypeError) {
+    match Level::read_from(data) {
+        Err(DeserializationError::ContentError {
+            offset,
+            section,
+            error,
+        }) => (offset, section, error),
+        other => panic!("expected content error, got {:?}", other),
+    }
+}
+
+#[test]
+fn golden_files_share_common_content() {
+    for version in 1..=VERSION {
+        let level = load_golden(version);
+
+        assert_eq!(level.tiles.len(), 12);
+        assert!(level.tiles.iter().all(|row| row.len() == 16));
+        assert_eq!(level.tiles[0][0].texture_type, TextureType::WALLS);
+        assert_eq!(level.tiles[0][15].id, 2);
+        assert_eq!(level.tiles[11][0].id, 32);
+        assert_eq!(level.tiles[1][14].texture_type, TextureType::FLOOR);
+        assert_eq!(level.tiles[1][14].shadow, 1);
+        assert_eq!(level.tiles[5][14].shadow, 2);
+        assert_eq!(level.p1_position, (1, 1));
+        assert_eq!(level.p2_position, (14, 10));
+
+        assert_eq!(
+            level.spotlights,
+            BTreeMap::from([((50, 60), 3), ((200, 100), 9)])
+        );
+        assert_eq!(
+            level.steams,
+            BTreeMap::from([
+                (
+                    (100, 40),
+                    Steam {
+                        range: 2,
+                        angle: 90
+                    }
+                ),
+                (
+                    (300, 200),
+                    Steam {
+                        range: 6,
+                        angle: 355
+                    }
+                ),
+            ])
+        );
+        assert_eq!(level.general_info.comment, format!("v{} golden", version));
+        assert_eq!(level.general_info.time_limit, 120);
+    }
+}
+
+#[test]
+fn golden_files_read_version_specific_tables() {
+    for version in 1..=VERSION {
+        let level = load_golden(version);
+        let enemy_types = if version >= 4 { 8 } else { 7 };
+        let (weapons, bullets) = match version {
+            1 => (9, 7),
+            2 => (10, 8),
+            _ => (11, 9),
+        };
+
+        for (index, &amount) in level.general_info.enemy_table.iter().enumerate() {
+            let expected = if index < enemy_types {
+                index as u32 + 1
+            } else {
+                0
+            };
+            assert_eq!(amount, expected, "version {} enemy {}", version, index);
+        }
+        for (crate_set, base) in [
+            (&level.crates.random.normal, 10),
+            (&level.crates.random.deathmatch, 20),
+        ] {
+            for (index, &amount) in crate_set.weapons.iter().enumerate() {
+                let expected = if index < weapons {
+                    base + index as u32
+                } else {
+                    0
+                };
+                assert_eq!(amount, expected, "version {} weapon {}", version, index);
+            }
+            for (index, &amount) in crate_set.bullets.iter().enumerate() {
+                let expected = if index < bullets {
+                    base + 50 + index as u32
+                } else {
+                    0
+                };
+                assert_eq!(amount, expected, "version {} bullet {}", version, index);
+            }
+            assert_eq!(crate_set.energy, base + 99);
+        }
+    }
+}
+
+#[test]
+fn golden_files_read_static_crates_from_version_5_only() {
+    for version in 1..VERSION {
+        assert!(load_golden(version).crates.staticc.is_empty());
+    }
+    assert_eq!(
+        load_golden(5).crates.staticc,
+        BTreeMap::from([
+            (
+                (40, 40),
+                StaticCrateType {
+                    crate_variant: StaticCrate::Normal,
+                    crate_class: CrateClass::Weapon,
+                    crate_type: 3,
+                }
+            ),
+            (
+                (80, 200),
+                StaticCrateType {
+                    crate_variant: StaticCrate::Normal,
+                    crate_class: CrateClass::Energy,
+                    crate_type: 0,
+                }
+            ),
+            (
+                (120, 60),
+                StaticCrateType {
+                    crate_variant: StaticCrate::Deathmatch,
+                    crate_class: CrateClass::Bullet,
+                    crate_type: 8,
+                }
+            ),
+        ])
+    );
+}
+
+#[test]
+fn golden_files_round_trip_byte_exact() {
+    for version in 1..=VERSION {
+        let data = read_golden(version);
+        let level = Level::read_from(&data[..]).unwrap();
+        assert_eq!(write(&level, version), data, "version {}", version);
+    }
+
+    let data = read_golden(5);
+    let mut written = Vec::new();
+    Level::read_from(&data[..])
+        .unwrap()
+        .write_to(&mut written)
+        .unwrap();
+    assert_eq!(written, data);
+}
+
+#[test]
+fn older_versions_report_what_they_lose() {
+    let level = load_golden(5);
+    assert!(level.get_version_losses(5).is_empty());
+    assert_eq!(
+        level.get_version_losses(4),
+        vec![VersionLoss::StaticCrates(3)]
+    );
+    assert_eq!(
+        level.get_version_losses(3),
+        vec![
+            VersionLoss::StaticCrates(3),
+            VersionLoss::Enemies {
+                index: 7,
+                amount: 8
+            },
+        ]
+    );
+    assert_eq!(level.get_version_losses(1).len(), 2 + 2 * 4);
+
+    let downgraded = Level::read_from(&write(&level, 2)[..]).unwrap();
+    assert_eq!(downgraded.crates.random.normal.weapons[9], 19);
+    assert_eq!(downgraded.crates.random.normal.weapons[10], 0);
+    assert_eq!(downgraded.crates.random.deathmatch.bullets[8], 0);
+    assert_eq!(downgraded.general_info.enemy_table[7], 0);
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    let level = load_golden(5);
+    for version in [0, VERSION + 1] {
+        assert!(matches!(
+            level.write_to_version(&mut Vec::new(), version),
+            Err(SerializationError::InvalidVersion(_))
+        ));
+    }
+
+    let mut data = read_golden(5);
+    data[0..4].copy_from_slice(&6u32.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (0, Section::Header, FileTypeError::InvalidVersion(6))
+    ));
+}
+
+#[test]
+fn truncated_files_name_the_section() {
+    let data = read_golden(5);
+    let tiles_end = 12 + 16 * 12 * 12;
+    for (length, expected_section) in [
+        (0, Section::Header),
+        (100, Section::Tiles),
+        (tiles_end + 4, Section::PlayerStarts),
+        (tiles_end + 20, Section::Spotlights),
+        (data.len() - 1, Section::StaticCrates),
+    ] {
+        let (_, section, error) = get_content_error(&data[..length]);
+        assert_eq!(section, expected_section, "length {}", length);
+        assert!(matches!(error, FileTypeError::UnexpectedEndOfFile));
+    }
+}
+
+#[test]
+fn hostile_values_are_rejected_with_offset() {
+    let mut data = read_golden(5);
+    data[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (4, Section::Header, FileTypeError::InvalidLevelSize(_))
+    ));
+
+    let mut data = read_golden(5);
+    data[24..28].copy_from_slice(&2u32.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (24, Section::Tiles, FileTypeError::InvalidTextureType(2))
+    ));
+
+    // Tile ids and shadows past the tile sheets
+    let mut data = read_golden(5);
+    data[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (28, Section::Tiles, FileTypeError::InvalidTileId(u32::MAX))
+    ));
+    let mut data = read_golden(5);
+    data[32..36].copy_from_slice(&7u32.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (32, Section::Tiles, FileTypeError::InvalidShadow(7))
+    ));
+
+    let spotlight_amount = 12 + 16 * 12 * 12 + 16;
+    let mut data = read_golden(5);
+    data[spotlight_amount..spotlight_amount + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (offset, Section::Spotlights, FileTypeError::TooManyItems(_))
+            if offset == spotlight_amount as u64
+    ));
+
+    let first_intensity = spotlight_amount + 12;
+    let mut data = read_golden(5);
+    data[first_intensity..first_intensity + 4].copy_from_slice(&10u32.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (
+            _,
+            Section::Spotlights,
+            FileTypeError::InvalidSpotlightIntensity(10)
+        )
+    ));
+
+    let last_crate_class = read_golden(5).len() - 16;
+    let mut data = read_golden(5);
+    data[last_crate_class..last_crate_class + 4].copy_from_slice(&3u32.to_le_bytes());
+    assert!(matches!(
+        get_content_error(&data),
+        (
+            _,
+            Section::StaticCrates,
+            FileTypeError::InvalidCrateClass(3)
+        )
+    ));
+}
+
+#[test]
+fn invalid_comments_are_refused() {
+    let mut level = load_golden(5);
+    level.general_info.comment = "a".repeat(20);
+    assert!(matches!(
+        level.write_to(&mut Vec::new()),
+        Err(SerializationError::InvalidComment)
+    ));
+
+    level.general_info.comment = "a".repeat(19);
+    assert!(level.write_to(&mut Vec::new()).is_ok());
+
+    level.general_info.comment = "\u{263a}".to_string();
+    assert!(matches!(
+        level.write_to(&mut Vec::new()),
+        Err(SerializationError::InvalidComment)
+    ));
+}
+
+#[test]
+fn player_starts_outside_level_are_refused() {
+    let mut level = load_golden(5);
+    level.p2_position = (16, 0);
+    assert!(matches!(
+        level.write_to(&mut Vec::new()),
+        Err(SerializationError::InvalidPlayerPosition)
+    ));
+
+    level.p2_position = (15, 11);
+    level.p1_position = (0, 12);
+    assert!(matches!(
+        level.write_to(&mut Vec::new()),
+        Err(SerializationError::InvalidPlayerPosition)
+    ));
+
+    level.p1_position = (0, 11);
+    assert!(level.write_to(&mut Vec::new()).is_ok());
+}
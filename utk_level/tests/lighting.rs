@@ -0,0 +1,42 @@
+use utk_level::lighting::{get_light_radius, AMBIENT_LIGHT};
+use utk_level::Level;
+
+#[test]
+fn level_without_spotlights_is_ambient() {
+    let level = Level::get_default_level((8, 6));
+    assert!(level.spotlights.is_empty());
+    let light_map = level.get_light_map(4);
+    assert_eq!((light_map.width, light_map.height), (40, 30));
+    assert!(light_map.values.iter().all(|&light| light == AMBIENT_LIGHT));
+    assert_eq!(level.get_light(50.0, 50.0), AMBIENT_LIGHT);
+}
+
+#[test]
+fn light_fades_with_distance_and_grows_with_intensity() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(100, 100), 3);
+    let near = level.get_light(100.0, 100.0);
+    let middle = level.get_light(100.0 + get_light_radius(3) / 2.0, 100.0);
+    let far = level.get_light(100.0 + get_light_radius(3), 100.0);
+    assert!(near > middle && middle > far);
+    assert_eq!(far, AMBIENT_LIGHT);
+
+    level.put_spotlight_to_level(&(100, 100), 9);
+    assert!(level.get_light(100.0, 100.0) > near);
+    assert!(level.get_light(100.0 + get_light_radius(3), 100.0) > AMBIENT_LIGHT);
+}
+
+#[test]
+fn light_map_matches_samples_and_stays_lit_at_most_fully() {
+    let mut level = Level::get_default_level((16, 12));
+    for x in [60, 70, 80] {
+        level.put_spotlight_to_level(&(x, 60), 9);
+    }
+    let light_map = level.get_light_map(2);
+    assert_eq!(light_map.get(71, 61), level.get_light(71.0, 61.0));
+    assert_eq!(light_map.get(301, 201), level.get_light(301.0, 201.0));
+    assert!(light_map.values.iter().all(|&light| light <= 1.0));
+    assert_eq!(light_map.get(70, 60), 1.0);
+    // Outside the level
+    assert_eq!(light_map.get(1000, 1000), AMBIENT_LIGHT);
+}
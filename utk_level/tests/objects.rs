@@ -0,0 +1,253 @@
+use std::collections::BTreeSet;
+use utk_level::crates::CrateClass;
+use utk_level::level::{StaticCrate, StaticCrateType, Steam};
+use utk_level::objects::{ObjectChange, ObjectKind, ObjectRef};
+use utk_level::Level;
+
+fn get_crate(
+    crate_variant: StaticCrate,
+    crate_class: CrateClass,
+    crate_type: u8,
+) -> StaticCrateType {
+    StaticCrateType {
+        crate_variant,
+        crate_class,
+        crate_type,
+    }
+}
+
+fn object(kind: ObjectKind, position: (u32, u32)) -> ObjectRef {
+    ObjectRef { kind, position }
+}
+
+#[test]
+fn objects_are_found_topmost_and_nearest_first() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(100, 100), 9);
+    level.put_spotlight_to_level(&(104, 100), 2);
+    level.put_steam_to_level(&(102, 102), &Steam { angle: 0, range: 1 });
+    level.put_crate_to_level(
+        &(95, 95),
+        &StaticCrateType {
+            crate_variant: StaticCrate::Normal,
+            crate_class: CrateClass::Energy,
+            crate_type: 0,
+        },
+    );
+    assert_eq!(
+        level.get_objects_at(&(103, 101), 2.0),
+        vec![
+            object(ObjectKind::Crate, (95, 95)),
+            object(ObjectKind::Steam, (102, 102)),
+            object(ObjectKind::Spotlight, (104, 100)),
+            object(ObjectKind::Spotlight, (100, 100)),
+        ]
+    );
+    // Objects are drawn at the same size on screen at any zoom
+    assert_eq!(
+        level.get_objects_at(&(100, 160), 0.5),
+        vec![object(ObjectKind::Spotlight, (100, 100))]
+    );
+    assert!(level.get_objects_at(&(100, 160), 2.0).is_empty());
+}
+
+#[test]
+fn moved_objects_keep_their_properties() {
+    let mut level = Level::get_default_level((16, 12));
+    let steam = Steam {
+        angle: 135,
+        range: 4,
+    };
+    level.put_steam_to_level(&(40, 40), &steam);
+    let moved = level.move_object(&object(ObjectKind::Steam, (40, 40)), &(41, 60));
+    assert_eq!(moved, Some(object(ObjectKind::Steam, (41, 60))));
+    assert_eq!(level.steams.len(), 1);
+    assert_eq!(level.get_steam_from_level(&(41, 60)), steam);
+
+    let crate_item = StaticCrateType {
+        crate_variant: StaticCrate::Deathmatch,
+        crate_class: CrateClass::Bullet,
+        crate_type: 3,
+    };
+    level.put_crate_to_level(&(10, 10), &crate_item);
+    level.move_object(&object(ObjectKind::Crate, (10, 10)), &(30, 10));
+    assert_eq!(*level.get_crate_from_level(&(30, 10)), crate_item);
+    assert!(!level.crates.staticc.contains_key(&(10, 10)));
+}
+
+#[test]
+fn objects_do_not_move_onto_others_or_out_of_the_level() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(20, 20), 1);
+    level.put_spotlight_to_level(&(40, 20), 5);
+    level.put_steam_to_level(&(60, 20), &Steam { angle: 0, range: 1 });
+    let original = level.clone();
+    let spotlight = object(ObjectKind::Spotlight, (20, 20));
+    assert_eq!(level.move_object(&spotlight, &(40, 20)), None);
+    assert_eq!(level.move_object(&spotlight, &(320, 20)), None);
+    assert_eq!(level.move_object(&spotlight, &(20, 240)), None);
+    assert_eq!(
+        level.move_object(&object(ObjectKind::Steam, (0, 0)), &(10, 10)),
+        None
+    );
+    assert_eq!(level, original);
+
+    // Objects of other kinds can share the pixel
+    assert_eq!(
+        level.move_object(&spotlight, &(60, 20)),
+        Some(object(ObjectKind::Spotlight, (60, 20)))
+    );
+    assert_eq!(level.get_spotlight_from_level(&(60, 20)), 1);
+    assert!(level.contains_object(&object(ObjectKind::Steam, (60, 20))));
+    assert!(!level.contains_object(&spotlight));
+}
+
+#[test]
+fn objects_are_selected_by_their_position_inside_a_box() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(20, 20), 1);
+    level.put_spotlight_to_level(&(100, 20), 1);
+    level.put_steam_to_level(&(60, 40), &Steam { angle: 0, range: 1 });
+    level.put_crate_to_level(
+        &(40, 60),
+        &get_crate(StaticCrate::Normal, CrateClass::Weapon, 0),
+    );
+    assert_eq!(
+        level.get_objects_in(&(60, 60), &(20, 20)),
+        vec![
+            object(ObjectKind::Spotlight, (20, 20)),
+            object(ObjectKind::Steam, (60, 40)),
+            object(ObjectKind::Crate, (40, 60)),
+        ]
+    );
+    assert!(level.get_objects_in(&(21, 21), &(39, 39)).is_empty());
+}
+
+#[test]
+fn objects_move_together_or_not_at_all() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(20, 20), 1);
+    level.put_spotlight_to_level(&(40, 20), 5);
+    level.put_spotlight_to_level(&(80, 20), 7);
+    level.put_steam_to_level(&(20, 40), &Steam { angle: 0, range: 1 });
+    let original = level.clone();
+    let selected = BTreeSet::from([
+        object(ObjectKind::Spotlight, (20, 20)),
+        object(ObjectKind::Spotlight, (40, 20)),
+        object(ObjectKind::Steam, (20, 40)),
+    ]);
+    // The spotlight at 80,20 is in the way, and then the level edge
+    assert_eq!(level.move_objects(&selected, (40, 0)), None);
+    assert_eq!(level.move_objects(&selected, (0, -21)), None);
+    assert_eq!(level, original);
+
+    // Onto the place of another moving spotlight
+    let moved = level.move_objects(&selected, (20, 0)).unwrap();
+    assert_eq!(
+        moved,
+        BTreeSet::from([
+            object(ObjectKind::Spotlight, (40, 20)),
+            object(ObjectKind::Spotlight, (60, 20)),
+            object(ObjectKind::Steam, (40, 40)),
+        ])
+    );
+    assert_eq!(level.get_spotlight_from_level(&(40, 20)), 1);
+    assert_eq!(level.get_spotlight_from_level(&(60, 20)), 5);
+    assert_eq!(level.spotlights.len(), 3);
+    assert!(!level.steams.contains_key(&(20, 40)));
+}
+
+#[test]
+fn bulk_changes_apply_to_their_kind_within_limits() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(20, 20), 8);
+    level.put_spotlight_to_level(&(40, 20), 2);
+    level.put_steam_to_level(&(60, 20), &Steam { angle: 0, range: 6 });
+    level.put_crate_to_level(
+        &(80, 20),
+        &get_crate(StaticCrate::Normal, CrateClass::Weapon, 4),
+    );
+    level.put_crate_to_level(
+        &(100, 20),
+        &get_crate(StaticCrate::Deathmatch, CrateClass::Energy, 0),
+    );
+    let selected: BTreeSet<_> = level
+        .get_objects_in(&(0, 0), &(200, 200))
+        .into_iter()
+        .collect();
+
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::Intensity(1)),
+        2
+    );
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::Intensity(1)),
+        1
+    );
+    assert_eq!(level.get_spotlight_from_level(&(20, 20)), 9);
+    assert_eq!(level.get_spotlight_from_level(&(40, 20)), 4);
+
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::SteamRange(1)),
+        0
+    );
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::SteamAngle(-5)),
+        1
+    );
+    assert_eq!(
+        level.get_steam_from_level(&(60, 20)),
+        Steam {
+            angle: 355,
+            range: 6
+        }
+    );
+
+    // Class changes start from the first crate type of the class
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::CrateClass(1)),
+        1
+    );
+    assert_eq!(
+        *level.get_crate_from_level(&(80, 20)),
+        get_crate(StaticCrate::Normal, CrateClass::Bullet, 0)
+    );
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::CrateType(-1)),
+        0
+    );
+    // Energy has only one crate type
+    assert_eq!(
+        level.change_objects(&selected, ObjectChange::CrateType(100)),
+        1
+    );
+    assert_eq!(level.get_crate_from_level(&(80, 20)).crate_type, 8);
+    assert_eq!(level.get_crate_from_level(&(100, 20)).crate_type, 0);
+
+    let deathmatch = ObjectChange::CrateVariant(StaticCrate::Deathmatch);
+    assert_eq!(level.change_objects(&selected, deathmatch), 1);
+    assert!(level
+        .crates
+        .staticc
+        .values()
+        .all(|crate_item| crate_item.crate_variant == StaticCrate::Deathmatch));
+}
+
+#[test]
+fn selected_objects_are_deleted() {
+    let mut level = Level::get_default_level((16, 12));
+    level.put_spotlight_to_level(&(20, 20), 1);
+    level.put_steam_to_level(&(20, 20), &Steam { angle: 0, range: 1 });
+    level.put_crate_to_level(
+        &(20, 20),
+        &get_crate(StaticCrate::Normal, CrateClass::Weapon, 0),
+    );
+    let selected = BTreeSet::from([
+        object(ObjectKind::Spotlight, (20, 20)),
+        object(ObjectKind::Crate, (20, 20)),
+        object(ObjectKind::Crate, (40, 40)),
+    ]);
+    assert_eq!(level.delete_objects(&selected), 2);
+    assert!(level.spotlights.is_empty() && level.crates.staticc.is_empty());
+    assert_eq!(level.steams.len(), 1);
+}
@@ -0,0 +1,154 @@
+use utk_level::image::Image;
+use utk_level::render::{render_level, render_minimap, Assets, RenderOptions};
+use utk_level::Level;
+
+fn assets() -> Assets {
+    Assets::load(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets")).unwrap()
+}
+
+fn load_golden(version: u32) -> Level {
+    let data = std::fs::read(format!(
+        "{}/tests/data/V{}.LEV",
+        env!("CARGO_MANIFEST_DIR"),
+        version
+    ))
+    .unwrap();
+    Level::read_from(&data[..]).unwrap()
+}
+
+#[test]
+fn rendered_image_covers_level_at_scale() {
+    let assets = assets();
+    let level = load_golden(5);
+    for scale in [1, 3] {
+        let image = render_level(
+            &level,
+            &assets,
+            &RenderOptions {
+                scale,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            (image.width, image.height),
+            (16 * 20 * scale, 12 * 20 * scale)
+        );
+    }
+}
+
+#[test]
+fn too_large_images_are_refused() {
+    let assets = assets();
+    let mut level = Level::get_default_level((16, 12));
+    level.tiles = vec![vec![level.tiles[0][0]; 1024]; 1024];
+    let error = render_level(
+        &level,
+        &assets,
+        &RenderOptions {
+            scale: 8,
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!((error.width, error.height), (163840, 163840));
+    assert!(Image::new(u32::MAX, 2).is_none());
+}
+
+#[test]
+fn tiles_are_copied_from_sheets_and_shadowed() {
+    let assets = assets();
+    let mut level = Level::get_default_level((4, 4));
+    level.p1_position = (3, 3);
+    level.p2_position = (3, 3);
+    let image = render_level(
+        &level,
+        &assets,
+        &RenderOptions {
+            scale: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Top left wall tile is id 0 of the walls sheet, pixels doubled
+    assert_eq!(image.get_pixel(0, 0), assets.walls.get_pixel(0, 0));
+    assert_eq!(image.get_pixel(11, 7), assets.walls.get_pixel(5, 3));
+
+    // Floor next to the top wall has a shadow blended over it
+    let floor = &level.tiles[1][1];
+    assert!(floor.shadow > 0);
+    let (x, y) = ((floor.id * 20) % 320, floor.id * 20 / 320 * 20);
+    let shadowed = (0..20)
+        .flat_map(|dy| (0..20).map(move |dx| (dx, dy)))
+        .filter(|&(dx, dy)| {
+            image.get_pixel(40 + dx * 2, 40 + dy * 2) != assets.floor.get_pixel(x + dx, y + dy)
+        })
+        .count();
+    assert!(shadowed > 0);
+}
+
+#[test]
+fn tile_ids_past_the_sheets_draw_nothing() {
+    let assets = assets();
+    let mut level = Level::get_default_level((4, 4));
+    level.tiles[0][0].id = u32::MAX;
+    level.tiles[0][0].shadow = u32::MAX;
+    level.tiles[0][1].id = u32::MAX / 20 + 1;
+    let image = render_level(&level, &assets, &RenderOptions::default()).unwrap();
+    let empty = Image::new(1, 1).unwrap().get_pixel(0, 0);
+    assert_eq!(image.get_pixel(0, 0), empty);
+    assert_eq!(image.get_pixel(30, 10), empty);
+}
+
+#[test]
+fn lighting_darkens_tiles_away_from_spotlights() {
+    let assets = assets();
+    let mut level = Level::get_default_level((8, 8));
+    level.spotlights.clear();
+    level.put_spotlight_to_level(&(40, 40), 9);
+    let plain = render_level(&level, &assets, &RenderOptions::default()).unwrap();
+    let lit = render_level(
+        &level,
+        &assets,
+        &RenderOptions {
+            lighting: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let brightness = |image: &Image, x, y| {
+        image.get_pixel(x, y)[..3]
+            .iter()
+            .map(|&c| c as u32)
+            .sum::<u32>()
+    };
+    // Next to the spotlight marker, and far from it
+    assert!(brightness(&lit, 40, 48) * 5 >= brightness(&plain, 40, 48) * 4);
+    assert!(brightness(&lit, 130, 130) * 2 < brightness(&plain, 130, 130));
+}
+
+#[test]
+fn png_output_decodes_back() {
+    let assets = assets();
+    let image = render_level(&load_golden(1), &assets, &RenderOptions::default()).unwrap();
+    let mut data = Vec::new();
+    image.write_png(&mut data).unwrap();
+    assert_eq!(Image::read_png(&data[..]).unwrap(), image);
+}
+
+#[test]
+fn minimap_has_pixel_per_tile() {
+    let mut level = Level::get_default_level((16, 12));
+    level.tiles[5][5].shadow = 0;
+    level.tiles[5][6].shadow = 3;
+    let image = render_minimap(&level);
+    assert_eq!((image.width, image.height), (16, 12));
+    let (wall, floor, shadowed) = (
+        image.get_pixel(0, 0),
+        image.get_pixel(5, 5),
+        image.get_pixel(6, 5),
+    );
+    assert_ne!(wall, floor);
+    assert!(shadowed[0] < floor[0] && shadowed[3] == 255);
+}
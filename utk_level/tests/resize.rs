@@ -0,0 +1,91 @@
+use utk_level::level::Steam;
+use utk_level::resize::{Resize, ResizeError, ResizeReport};
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+const WALL: Tile = Tile {
+    texture_type: TextureType::WALLS,
+    id: 5,
+    shadow: 0,
+};
+
+fn get_level(size: (u8, u8)) -> Level {
+    let mut level = Level::get_default_level(size);
+    level.tiles[1][1].id = 9;
+    level.p1_position = (1, 1);
+    level.p2_position = (size.0 as u32 - 2, size.1 as u32 - 2);
+    level.put_spotlight_to_level(&(25, 25), 3);
+    level.put_steam_to_level(
+        &(size.0 as u32 * 20 - 20, size.1 as u32 * 20 - 20),
+        &Steam { range: 1, angle: 0 },
+    );
+    level
+}
+
+#[test]
+fn growing_shifts_content_and_pads_with_fill() {
+    let mut level = get_level((16, 12));
+    let resize = Resize {
+        left: 2,
+        right: 1,
+        top: 3,
+        bottom: 0,
+    };
+    assert!(level.get_resize_report(&resize).unwrap().is_empty());
+
+    let report = level.resize(&resize, &WALL).unwrap();
+    assert!(report.is_empty());
+    assert_eq!(level.tiles.len(), 15);
+    assert!(level.tiles.iter().all(|row| row.len() == 19));
+    assert_eq!(level.tiles[0][0], WALL);
+    assert_eq!(level.tiles[14][18], WALL);
+    assert_eq!(level.tiles[4][3].id, 9);
+    assert_eq!(level.p1_position, (3, 4));
+    assert_eq!(level.p2_position, (16, 13));
+    assert_eq!(level.spotlights.get(&(65, 85)), Some(&3));
+    assert!(level.steams.contains_key(&(340, 280)));
+}
+
+#[test]
+fn cropping_drops_objects_and_moves_player_starts() {
+    let mut level = get_level((20, 14));
+    let resize = Resize {
+        left: -2,
+        right: -2,
+        top: 0,
+        bottom: -2,
+    };
+    let expected = ResizeReport {
+        spotlights: 1,
+        steams: 1,
+        crates: 0,
+        p1_moved: true,
+        p2_moved: true,
+    };
+    assert_eq!(level.get_resize_report(&resize).unwrap(), expected);
+    assert_eq!(level.resize(&resize, &WALL).unwrap(), expected);
+    assert_eq!(
+        expected.to_string(),
+        "dropped 1 spotlights, dropped 1 steams, moved PL1 start, moved PL2 start"
+    );
+
+    assert_eq!((level.tiles[0].len(), level.tiles.len()), (16, 12));
+    assert_eq!(level.p1_position, (0, 1));
+    assert_eq!(level.p2_position, (15, 11));
+    assert!(level.spotlights.is_empty());
+    assert!(level.steams.is_empty());
+}
+
+#[test]
+fn too_small_levels_are_refused() {
+    let mut level = get_level((16, 12));
+    let resize = Resize {
+        right: -1,
+        ..Resize::default()
+    };
+    assert_eq!(
+        level.resize(&resize, &WALL),
+        Err(ResizeError::InvalidLevelSize(15, 12))
+    );
+    assert_eq!(level, get_level((16, 12)));
+}
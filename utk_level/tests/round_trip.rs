@@ -0,0 +1,144 @@
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+use utk_level::crates::{get_crates, CrateClass};
+use utk_level::level::{
+    CrateSet, Crates, GeneralInfo, RandomCrates, StaticCrate, StaticCrateType, Steam, DIFF_BULLETS,
+    DIFF_ENEMIES, DIFF_WEAPONS, OLDEST_VERSION, SHADOW_TILES, SHEET_TILES, TILE_SIZE, VERSION,
+};
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+fn coordinates() -> impl Strategy<Value = (u32, u32)> {
+    (0..64 * TILE_SIZE, 0..64 * TILE_SIZE)
+}
+
+fn tile() -> impl Strategy<Value = Tile> {
+    (
+        prop_oneof![Just(TextureType::FLOOR), Just(TextureType::WALLS)],
+        0..SHEET_TILES,
+        0..=SHADOW_TILES,
+    )
+        .prop_map(|(texture_type, id, shadow)| Tile {
+            texture_type,
+            id,
+            shadow,
+        })
+}
+
+fn crate_set() -> impl Strategy<Value = CrateSet> {
+    (
+        vec(any::<u32>(), DIFF_WEAPONS as usize),
+        vec(any::<u32>(), DIFF_BULLETS as usize),
+        any::<u32>(),
+    )
+        .prop_map(|(weapons, bullets, energy)| CrateSet {
+            weapons: weapons.try_into().unwrap(),
+            bullets: bullets.try_into().unwrap(),
+            energy,
+        })
+}
+
+fn static_crate() -> impl Strategy<Value = StaticCrateType> {
+    (any::<bool>(), 0..3u32)
+        .prop_flat_map(|(deathmatch, class)| {
+            let types = get_crates()[class as usize].len() as u8;
+            (Just(deathmatch), Just(class), 0..types)
+        })
+        .prop_map(|(deathmatch, class, crate_type)| StaticCrateType {
+            crate_variant: if deathmatch {
+                StaticCrate::Deathmatch
+            } else {
+                StaticCrate::Normal
+            },
+            crate_class: CrateClass::from_u32(class).unwrap(),
+            crate_type,
+        })
+}
+
+fn level() -> impl Strategy<Value = Level> {
+    (1..24usize, 1..24usize)
+        .prop_flat_map(|(x_size, y_size)| {
+            (
+                vec(vec(tile(), x_size), y_size),
+                (0..x_size as u32, 0..y_size as u32),
+                (0..x_size as u32, 0..y_size as u32),
+                btree_map(coordinates(), 0..10u8, 0..8),
+                btree_map(
+                    coordinates(),
+                    (0..7u8, 0..72u16).prop_map(|(range, angle)| Steam {
+                        range,
+                        angle: angle * 5,
+                    }),
+                    0..8,
+                ),
+                (
+                    "[a-zA-Z0-9 \u{e4}\u{f6}]{0,19}",
+                    any::<u32>(),
+                    vec(any::<u32>(), DIFF_ENEMIES as usize),
+                ),
+                (crate_set(), crate_set()),
+                btree_map(coordinates(), static_crate(), 0..8),
+            )
+        })
+        .prop_map(
+            |(
+                tiles,
+                p1_position,
+                p2_position,
+                spotlights,
+                steams,
+                (comment, time_limit, enemy_table),
+                (normal, deathmatch),
+                staticc,
+            )| Level {
+                tiles,
+                p1_position,
+                p2_position,
+                scroll: (0, 0),
+                spotlights,
+                steams,
+                general_info: GeneralInfo {
+                    comment,
+                    time_limit,
+                    enemy_table: enemy_table.try_into().unwrap(),
+                },
+                crates: Crates {
+                    random: RandomCrates { normal, deathmatch },
+                    staticc,
+                },
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn level_survives_round_trip(level in level()) {
+        let mut data = Vec::new();
+        level.write_to(&mut data).unwrap();
+        let read = Level::read_from(&data[..]).unwrap();
+
+        let mut rewritten = Vec::new();
+        read.write_to(&mut rewritten).unwrap();
+        prop_assert_eq!(&read, &level);
+        prop_assert_eq!(rewritten, data);
+    }
+
+    #[test]
+    fn older_versions_are_stable_after_first_save(
+        level in level(),
+        version in OLDEST_VERSION..VERSION,
+    ) {
+        let mut data = Vec::new();
+        level.write_to_version(&mut data, version).unwrap();
+        let read = Level::read_from(&data[..]).unwrap();
+
+        prop_assert!(read.get_version_losses(version).is_empty());
+        prop_assert_eq!(&read.tiles, &level.tiles);
+        prop_assert_eq!(&read.spotlights, &level.spotlights);
+        prop_assert_eq!(&read.steams, &level.steams);
+
+        let mut rewritten = Vec::new();
+        read.write_to_version(&mut rewritten, version).unwrap();
+        prop_assert_eq!(rewritten, data);
+    }
+}
@@ -0,0 +1,72 @@
+use utk_level::area::Area;
+use utk_level::shape::Shape;
+use utk_level::types::TextureType;
+use utk_level::Level;
+
+#[test]
+fn line_has_one_tile_per_step() {
+    assert_eq!(
+        Shape::Line.get_tiles(&(1, 1), &(7, 3)),
+        vec![(1, 1), (2, 1), (3, 2), (4, 2), (5, 2), (6, 3), (7, 3)]
+    );
+    let diagonal = Shape::Line.get_tiles(&(4, 4), &(0, 0));
+    assert_eq!(diagonal, (0..5).map(|i| (i, i)).collect::<Vec<_>>());
+    assert_eq!(Shape::Line.get_tiles(&(2, 2), &(2, 2)), vec![(2, 2)]);
+}
+
+#[test]
+fn rectangle_is_hollow() {
+    let tiles = Shape::Rectangle.get_tiles(&(6, 5), &(2, 2));
+    assert_eq!(tiles.len(), 2 * 5 + 2 * 4 - 4);
+    assert!(tiles.contains(&(2, 2)) && tiles.contains(&(6, 5)));
+    assert!(!tiles.contains(&(4, 3)));
+}
+
+#[test]
+fn ellipse_touches_every_side_of_its_box() {
+    for (width, height) in [(1, 1), (2, 5), (5, 5), (8, 3), (12, 7)] {
+        let tiles = Shape::Ellipse.get_tiles(&(3, 4), &(3 + width - 1, 4 + height - 1));
+        let area = Area {
+            x: 3,
+            y: 4,
+            width,
+            height,
+        };
+        assert!(tiles.iter().all(|tile| area.contains_tile(tile)));
+        assert!(tiles.iter().any(|tile| tile.0 == 3));
+        assert!(tiles.iter().any(|tile| tile.0 == 3 + width - 1));
+        assert!(tiles.iter().any(|tile| tile.1 == 4));
+        assert!(tiles.iter().any(|tile| tile.1 == 4 + height - 1));
+        // Mirror symmetric
+        for tile in &tiles {
+            assert!(tiles.contains(&(2 * 3 + width - 1 - tile.0, tile.1)));
+            assert!(tiles.contains(&(tile.0, 2 * 4 + height - 1 - tile.1)));
+        }
+    }
+    let circle = Shape::Ellipse.get_tiles(&(0, 0), &(4, 4));
+    assert!(!circle.contains(&(0, 0)) && !circle.contains(&(2, 2)));
+}
+
+#[test]
+fn draw_shape_stays_inside_level() {
+    let mut level = Level::get_default_level((16, 12));
+    let area = level.draw_shape(
+        Shape::Rectangle,
+        &(10, 8),
+        &(20, 20),
+        4,
+        &TextureType::WALLS,
+    );
+    assert_eq!(
+        area,
+        Area {
+            x: 10,
+            y: 8,
+            width: 6,
+            height: 4,
+        }
+    );
+    assert_eq!(level.tiles[8][15].texture_type, TextureType::WALLS);
+    assert_eq!(level.tiles[8][10].id, 4);
+    assert_eq!(level.tiles[9][11].texture_type, TextureType::FLOOR);
+}
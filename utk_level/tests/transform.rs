@@ -0,0 +1,126 @@
+use utk_level::area::Area;
+use utk_level::crates::CrateClass;
+use utk_level::level::{StaticCrate, StaticCrateType, Steam};
+use utk_level::resize::ResizeError;
+use utk_level::transform::Transform;
+use utk_level::types::{TextureType, Tile};
+use utk_level::Level;
+
+const FLOOR: Tile = Tile {
+    texture_type: TextureType::FLOOR,
+    id: 0,
+    shadow: 0,
+};
+
+fn get_wall(id: u32) -> Tile {
+    Tile {
+        texture_type: TextureType::WALLS,
+        id,
+        shadow: 0,
+    }
+}
+
+fn get_level() -> Level {
+    let mut level = Level::get_default_level((20, 16));
+    // Top left corner of a grey block and a red brick top edge
+    level.tiles[1][2] = get_wall(0);
+    level.tiles[1][3] = get_wall(81);
+    level.put_spotlight_to_level(&(45, 25), 3);
+    level.put_steam_to_level(
+        &(110, 70),
+        &Steam {
+            angle: 30,
+            range: 2,
+        },
+    );
+    level.put_crate_to_level(
+        &(200, 100),
+        &StaticCrateType {
+            crate_variant: StaticCrate::Deathmatch,
+            crate_class: CrateClass::Bullet,
+            crate_type: 1,
+        },
+    );
+    level.p1_position = (1, 2);
+    level.p2_position = (18, 13);
+    level
+}
+
+#[test]
+fn flipping_mirrors_tiles_and_objects() {
+    let mut level = get_level();
+    level.transform(Transform::FlipHorizontal).unwrap();
+    assert_eq!(level.tiles[1][17], get_wall(2));
+    assert_eq!(level.tiles[1][16], get_wall(81));
+    assert_eq!(level.get_spotlight_from_level(&(354, 25)), 3);
+    let steam = level.get_steam_from_level(&(289, 70));
+    assert_eq!(steam.angle, 330);
+    // Crate box is mirrored as a whole
+    assert!(level.crates.staticc.contains_key(&(172, 100)));
+    assert_eq!(level.p1_position, (18, 2));
+    assert_eq!(level.p2_position, (1, 13));
+
+    level.transform(Transform::FlipVertical).unwrap();
+    assert_eq!(level.tiles[14][17], get_wall(18));
+    assert_eq!(level.tiles[14][16], get_wall(97));
+    assert_eq!(level.get_steam_from_level(&(289, 249)).angle, 210);
+}
+
+#[test]
+fn rotating_four_times_restores_level() {
+    let original = get_level();
+    let mut level = original.clone();
+    level.transform(Transform::RotateClockwise).unwrap();
+    assert_eq!((level.tiles[0].len(), level.tiles.len()), (16, 20));
+    // Top left corner becomes top right corner, top edge becomes right edge
+    assert_eq!(level.tiles[2][14], get_wall(2));
+    assert_eq!(level.tiles[3][14], get_wall(98));
+    assert_eq!(level.get_steam_from_level(&(249, 110)).angle, 300);
+    assert_eq!(level.p1_position, (13, 1));
+    for _ in 0..3 {
+        level.transform(Transform::RotateClockwise).unwrap();
+    }
+    assert_eq!(level, original);
+
+    level.transform(Transform::RotateCounterClockwise).unwrap();
+    level.transform(Transform::RotateClockwise).unwrap();
+    assert_eq!(level, original);
+}
+
+#[test]
+fn rotating_refuses_too_narrow_result() {
+    let mut level = Level::get_default_level((16, 12));
+    assert_eq!(
+        level.transform(Transform::RotateClockwise),
+        Err(ResizeError::InvalidLevelSize(12, 16))
+    );
+}
+
+#[test]
+fn area_is_transformed_in_place() {
+    let mut level = get_level();
+    let area = Area {
+        x: 1,
+        y: 1,
+        width: 4,
+        height: 2,
+    };
+    let transformed = level.transform_area(&area, Transform::RotateClockwise, &FLOOR);
+    assert_eq!(
+        transformed,
+        Area {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 4,
+        }
+    );
+    assert_eq!(level.tiles[2][2], get_wall(2));
+    assert_eq!(level.tiles[3][2], get_wall(98));
+    // Uncovered part of the original area is filled
+    assert_eq!(level.tiles[1][4], FLOOR);
+    assert_eq!(level.get_spotlight_from_level(&(54, 45)), 3);
+    assert_eq!(level.p1_position, (1, 1));
+    // Objects outside of the area stay
+    assert_eq!(level.get_steam_from_level(&(110, 70)).angle, 30);
+}
@@ -0,0 +1,80 @@
+use utk_level::area::Area;
+use utk_level::types::TextureType;
+use utk_level::variation::VariationBrush;
+use utk_level::Level;
+
+fn get_brush(seed: u64) -> VariationBrush {
+    let mut brush = VariationBrush::new(TextureType::FLOOR, seed);
+    brush.add(1);
+    brush.add(2);
+    // Tile 3 is three times as common as the others
+    for _ in 0..3 {
+        brush.add(3);
+    }
+    brush
+}
+
+fn get_whole_level(level: &Level) -> Area {
+    Area {
+        x: 0,
+        y: 0,
+        width: level.tiles[0].len() as u32,
+        height: level.tiles.len() as u32,
+    }
+}
+
+#[test]
+fn picks_follow_weights() {
+    let brush = get_brush(7);
+    assert_eq!(brush.get_weight(3), 3);
+    assert_eq!(brush.get_weight(4), 0);
+    let mut counts = [0; 4];
+    for y in 0..50 {
+        for x in 0..100 {
+            counts[brush.get_id(&(x, y)) as usize] += 1;
+        }
+    }
+    assert_eq!(counts[0], 0);
+    assert!((800..1200).contains(&counts[1]), "{:?}", counts);
+    assert!((800..1200).contains(&counts[2]), "{:?}", counts);
+    assert!((2700..3300).contains(&counts[3]), "{:?}", counts);
+}
+
+#[test]
+fn same_seed_paints_same_tiles() {
+    let mut first = Level::get_default_level((16, 12));
+    let mut second = Level::get_default_level((16, 12));
+    let tiles = get_whole_level(&first).get_tiles();
+    first.put_random_tiles(&tiles, &get_brush(1));
+    // Painting order does not matter
+    let reversed: Vec<_> = tiles.iter().rev().copied().collect();
+    second.put_random_tiles(&reversed, &get_brush(1));
+    assert_eq!(first.tiles, second.tiles);
+
+    second.put_random_tiles(&tiles, &get_brush(2));
+    assert_ne!(first.tiles, second.tiles);
+}
+
+#[test]
+fn randomize_keeps_other_tiles() {
+    let mut level = Level::get_default_level((16, 12));
+    let area = Area {
+        x: 2,
+        y: 2,
+        width: 6,
+        height: 5,
+    };
+    level.put_random_tiles(&area.get_tiles(), &get_brush(1));
+    level.tiles[3][3].id = 9;
+    let before = level.clone();
+
+    let changed = level.randomize_area(&get_whole_level(&before), &get_brush(2));
+    assert!(changed.is_some_and(|changed| area.contains_tile(&(changed.x, changed.y))));
+    assert_eq!(level.tiles[3][3].id, 9);
+    assert_eq!(level.tiles[0][0], before.tiles[0][0]);
+    assert_eq!(level.tiles[1][1], before.tiles[1][1]);
+    assert!(area
+        .get_tiles()
+        .iter()
+        .all(|&(x, y)| level.tiles[y as usize][x as usize].id != 0));
+}